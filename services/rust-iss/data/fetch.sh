@@ -0,0 +1,10 @@
+#!/bin/sh
+# Границы Natural Earth 1:110m (v5.1.2) для офлайн-геокодирования /iss/over.
+# Файлы хранятся в репозитории рядом со скриптом и копируются в образ (см. Dockerfile);
+# скрипт нужен только для первичной загрузки или обновления версии.
+set -eu
+cd "$(dirname "$0")"
+base=https://raw.githubusercontent.com/nvkelso/natural-earth-vector/v5.1.2/geojson
+for f in ne_110m_admin_0_countries.geojson ne_110m_geography_marine_polys.geojson; do
+    curl -fsSL -o "$f" "$base/$f"
+done
@@ -1,12 +1,25 @@
 use crate::clients::HttpClient;
 use crate::config::Config;
+use crate::domain::PositionEvent;
+use crate::geo::RegionIndex;
+use crate::jobs::JobRegistry;
+use crate::leader::LeaderElection;
 use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub http_client: HttpClient,
     pub config: Config,
+    pub events: broadcast::Sender<PositionEvent>,
+    pub regions: Arc<RegionIndex>,
+    pub jobs: Arc<JobRegistry>,
+    pub leader: Arc<LeaderElection>,
+    // отменяется при остановке сервиса
+    pub shutdown: CancellationToken,
 }
 
 
@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const TWO_PI: f64 = 2.0 * PI;
+// WGS-84
+const WGS84_A: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+
+pub fn julian_date(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 86400000.0 + 2440587.5
+}
+
+// Гринвичское среднее звёздное время (IAU-82), радианы. UT1 ~= UTC.
+pub fn gmst(at: DateTime<Utc>) -> f64 {
+    let tut1 = (julian_date(at) - 2451545.0) / 36525.0;
+    let secs = -6.2e-6 * tut1.powi(3)
+        + 0.093104 * tut1 * tut1
+        + (876600.0 * 3600.0 + 8640184.812866) * tut1
+        + 67310.54841;
+    let g = (secs.to_radians() / 240.0) % TWO_PI;
+    if g < 0.0 {
+        g + TWO_PI
+    } else {
+        g
+    }
+}
+
+// TEME -> ECEF (без учёта движения полюсов)
+pub fn teme_to_ecef(r: [f64; 3], at: DateTime<Utc>) -> [f64; 3] {
+    let (s, c) = gmst(at).sin_cos();
+    [c * r[0] + s * r[1], -s * r[0] + c * r[1], r[2]]
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Geodetic {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+}
+
+pub fn ecef_to_geodetic(r: [f64; 3]) -> Geodetic {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let p = (r[0] * r[0] + r[1] * r[1]).sqrt();
+    let longitude = r[1].atan2(r[0]);
+    let mut lat = r[2].atan2(p * (1.0 - e2));
+    let mut n = WGS84_A;
+    for _ in 0..6 {
+        let s = lat.sin();
+        n = WGS84_A / (1.0 - e2 * s * s).sqrt();
+        lat = (r[2] + n * e2 * s).atan2(p);
+    }
+    let altitude_km = if lat.cos().abs() > 1.0e-9 {
+        p / lat.cos() - n
+    } else {
+        r[2].abs() - n * (1.0 - e2)
+    };
+    Geodetic {
+        latitude: lat.to_degrees(),
+        longitude: longitude.to_degrees(),
+        altitude_km,
+    }
+}
+
+pub fn teme_to_geodetic(r: [f64; 3], at: DateTime<Utc>) -> Geodetic {
+    ecef_to_geodetic(teme_to_ecef(r, at))
+}
+
+pub fn geodetic_to_ecef(lat_deg: f64, lon_deg: f64, alt_km: f64) -> [f64; 3] {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let (slat, clat) = lat_deg.to_radians().sin_cos();
+    let (slon, clon) = lon_deg.to_radians().sin_cos();
+    let n = WGS84_A / (1.0 - e2 * slat * slat).sqrt();
+    [
+        (n + alt_km) * clat * clon,
+        (n + alt_km) * clat * slon,
+        (n * (1.0 - e2) + alt_km) * slat,
+    ]
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub range_km: f64,
+}
+
+// Азимут/угол места спутника для наблюдателя (топоцентрическая SEZ система)
+pub fn look_angles(lat_deg: f64, lon_deg: f64, alt_km: f64, sat_ecef: [f64; 3]) -> LookAngles {
+    let obs = geodetic_to_ecef(lat_deg, lon_deg, alt_km);
+    let d = [sat_ecef[0] - obs[0], sat_ecef[1] - obs[1], sat_ecef[2] - obs[2]];
+    let (slat, clat) = lat_deg.to_radians().sin_cos();
+    let (slon, clon) = lon_deg.to_radians().sin_cos();
+
+    let south = slat * clon * d[0] + slat * slon * d[1] - clat * d[2];
+    let east = -slon * d[0] + clon * d[1];
+    let zenith = clat * clon * d[0] + clat * slon * d[1] + slat * d[2];
+    let range_km = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+
+    let mut azimuth = east.atan2(-south).to_degrees();
+    if azimuth < 0.0 {
+        azimuth += 360.0;
+    }
+    LookAngles {
+        azimuth,
+        elevation: (zenith / range_km).asin().to_degrees(),
+        range_km,
+    }
+}
+
+const AU_KM: f64 = 149597870.7;
+const SUN_RADIUS_KM: f64 = 696000.0;
+const EARTH_RADIUS_KM: f64 = 6378.137;
+
+// Положение Солнца (Astronomical Almanac, low precision, ~0.01°)
+// в экваториальной системе даты; TEME от неё отличается на доли градуса.
+pub fn sun_eci(at: DateTime<Utc>) -> [f64; 3] {
+    let n = julian_date(at) - 2451545.0;
+    let l = (280.460 + 0.9856474 * n).to_radians();
+    let g = (357.528 + 0.9856003 * n).to_radians();
+    let lambda = l + (1.915 * g.sin() + 0.020 * (2.0 * g).sin()).to_radians();
+    let eps = (23.439 - 0.0000004 * n).to_radians();
+    let r = (1.00014 - 0.01671 * g.cos() - 0.00014 * (2.0 * g).cos()) * AU_KM;
+    [r * lambda.cos(), r * eps.cos() * lambda.sin(), r * eps.sin() * lambda.sin()]
+}
+
+pub fn sun_ecef(at: DateTime<Utc>) -> [f64; 3] {
+    teme_to_ecef(sun_eci(at), at)
+}
+
+// Подсолнечная точка (широта, долгота), градусы
+pub fn subsolar_point(at: DateTime<Utc>) -> (f64, f64) {
+    let g = ecef_to_geodetic(sun_ecef(at));
+    (g.latitude, g.longitude)
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// Коническая модель тени Земли: видимые угловые радиусы Земли и Солнца
+// со спутника сравниваются с углом между их центрами.
+pub fn shadow(sat_ecef: [f64; 3], sun_ecef: [f64; 3]) -> Shadow {
+    let to_earth = [-sat_ecef[0], -sat_ecef[1], -sat_ecef[2]];
+    let to_sun = [sun_ecef[0] - sat_ecef[0], sun_ecef[1] - sat_ecef[1], sun_ecef[2] - sat_ecef[2]];
+    let (d_earth, d_sun) = (norm(to_earth), norm(to_sun));
+    let earth_r = (EARTH_RADIUS_KM / d_earth).min(1.0).asin();
+    let sun_r = (SUN_RADIUS_KM / d_sun).asin();
+    let sep = (dot(to_earth, to_sun) / (d_earth * d_sun)).clamp(-1.0, 1.0).acos();
+    if sep < earth_r - sun_r {
+        Shadow::Umbra
+    } else if sep < earth_r + sun_r {
+        Shadow::Penumbra
+    } else {
+        Shadow::Sunlit
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shadow {
+    Sunlit,
+    Penumbra,
+    Umbra,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Illumination {
+    pub shadow: Shadow,
+    pub ground: String,
+    pub sun_elevation: f64,
+    pub subsolar_lat: f64,
+    pub subsolar_lon: f64,
+}
+
+// Освещённость спутника и подспутниковой точки
+pub fn illumination(lat_deg: f64, lon_deg: f64, alt_km: f64, at: DateTime<Utc>) -> Illumination {
+    let sun = sun_ecef(at);
+    let sat = geodetic_to_ecef(lat_deg, lon_deg, alt_km);
+    let sun_elevation = look_angles(lat_deg, lon_deg, 0.0, sun).elevation;
+    let sub = ecef_to_geodetic(sun);
+    Illumination {
+        shadow: shadow(sat, sun),
+        // -0.833° - стандартная поправка на рефракцию и диск Солнца
+        ground: if sun_elevation > -0.833 { "day" } else { "night" }.to_string(),
+        sun_elevation,
+        subsolar_lat: sub.latitude,
+        subsolar_lon: sub.longitude,
+    }
+}
+
+// Ночная сторона Земли как кольцо GeoJSON [lon, lat]: линия терминатора
+// по долготам с шагом 1° и замыкание через полюс, погружённый в ночь.
+pub fn night_polygon(at: DateTime<Utc>) -> Vec<[f64; 2]> {
+    let (dec, sub_lon) = subsolar_point(at);
+    // при склонении ~0 терминатор идёт по меридианам; избегаем деления на 0
+    let dec = if dec.abs() < 1.0e-6 { 1.0e-6 } else { dec };
+    let tan_dec = dec.to_radians().tan();
+
+    let mut ring: Vec<[f64; 2]> = (-180..=180)
+        .map(|lon| {
+            let ha = (lon as f64 - sub_lon).to_radians();
+            let lat = (-ha.cos() / tan_dec).atan().to_degrees();
+            [lon as f64, lat]
+        })
+        .collect();
+
+    let pole = if dec > 0.0 { -90.0 } else { 90.0 };
+    ring.push([180.0, pole]);
+    ring.push([-180.0, pole]);
+    ring.push(ring[0]);
+    ring
+}
@@ -1,13 +1,111 @@
 use crate::config::Config;
-use crate::error::ApiError;
-use reqwest::Client;
+use crate::error::{ApiError, UpstreamError};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
-use std::time::Duration;
+use std::cell::Cell;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+tokio::task_local! {
+    static UPSTREAM_MS: Cell<u64>;
+}
+
+// Выполняет future и возвращает суммарное время ожидания ответов источников
+// (все запросы HttpClient внутри future, включая повторы)
+pub async fn measure_upstream<F: Future>(fut: F) -> (F::Output, u64) {
+    UPSTREAM_MS
+        .scope(Cell::new(0), async move {
+            let out = fut.await;
+            (out, UPSTREAM_MS.with(Cell::get))
+        })
+        .await
+}
+
+fn record_upstream(started: Instant) {
+    let _ = UPSTREAM_MS.try_with(|ms| ms.set(ms.get() + started.elapsed().as_millis() as u64));
+}
+
+// сколько символов тела ответа попадает в UpstreamError
+const BODY_SNIPPET_CHARS: usize = 300;
+
+// Повторы запросов к источникам: только временные сбои (408, 429, 5xx, сеть),
+// экспоненциальная пауза с full jitter, Retry-After от источника в приоритете
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_secs(config.retry_delay_secs),
+            max_delay: Duration::from_secs(config.retry_max_delay_secs.max(config.retry_delay_secs)),
+        }
+    }
+
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    // сетевые ошибки и таймауты; неверный URL или цикл редиректов не повторяются
+    pub fn is_retryable_error(e: &reqwest::Error) -> bool {
+        !e.is_builder() && !e.is_redirect() && !e.is_status()
+    }
+
+    // Пауза перед повтором после попытки attempt (с 0); None - попытки исчерпаны
+    // или источник просит ждать дольше max_delay
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        if let Some(wait) = retry_after {
+            return (wait <= self.max_delay).then_some(wait);
+        }
+        let cap = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        Some(cap.mul_f64(rand::thread_rng().gen::<f64>()))
+    }
+}
+
+// Retry-After: секунды или HTTP-дата
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
+
+async fn upstream_error(url: &str, resp: reqwest::Response, attempts: u32) -> UpstreamError {
+    let status = resp.status().as_u16();
+    let body = resp.text().await.unwrap_or_default();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let body = match body.char_indices().nth(BODY_SNIPPET_CHARS) {
+        Some((cut, _)) => format!("{}...", &body[..cut]),
+        None => body,
+    };
+    UpstreamError {
+        url: url.split('?').next().unwrap_or(url).to_string(),
+        status,
+        body,
+        attempts,
+    }
+}
 
 #[derive(Clone)]
 pub struct HttpClient {
     client: Client,
     config: Config,
+    retry: RetryPolicy,
 }
 
 impl HttpClient {
@@ -17,45 +115,68 @@ impl HttpClient {
             .user_agent("rust_iss/1.0")
             .build()?;
         
-        Ok(Self { client, config })
+        let retry = RetryPolicy::from_config(&config);
+        Ok(Self { client, config, retry })
     }
 
     pub async fn get_with_retry(&self, url: &str) -> Result<Value, ApiError> {
-        let mut last_error = None;
-        
-        for attempt in 0..=self.config.max_retries {
-            match self.client.get(url).send().await {
+        Ok(self.send_with_retry(url).await?.json().await?)
+    }
+
+    pub async fn get_text_with_retry(&self, url: &str) -> Result<String, ApiError> {
+        Ok(self.send_with_retry(url).await?.text().await?)
+    }
+
+    // Доставка уведомлений: одна попытка, повтор - следующим событием
+    pub async fn post_json(&self, url: &str, body: &Value) -> Result<(), ApiError> {
+        let started = Instant::now();
+        let res = self.client.post(url).json(body).send().await;
+        record_upstream(started);
+        let resp = res?;
+        if !resp.status().is_success() {
+            return Err(ApiError::Upstream(upstream_error(url, resp, 1).await));
+        }
+        Ok(())
+    }
+
+    // Ошибка - последней попытки: UpstreamError со статусом и телом или сетевая
+    async fn send_with_retry(&self, url: &str) -> Result<reqwest::Response, ApiError> {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            let res = self.client.get(url).send().await;
+            record_upstream(started);
+            let (err, wait) = match res {
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
                 Ok(resp) => {
-                    if resp.status().is_success() {
-                        return Ok(resp.json().await?);
-                    } else if attempt < self.config.max_retries {
-                        tokio::time::sleep(Duration::from_secs(self.config.retry_delay_secs)).await;
-                        continue;
-                    } else {
-                        // Если статус не успешный, используем последнюю ошибку или создаем новую
-                        // Просто продолжаем к последней ошибке
-                    }
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    if attempt < self.config.max_retries {
-                        tokio::time::sleep(Duration::from_secs(self.config.retry_delay_secs)).await;
+                    let retryable = RetryPolicy::is_retryable_status(resp.status());
+                    let wait = retry_after(resp.headers());
+                    let err = ApiError::Upstream(upstream_error(url, resp, attempt + 1).await);
+                    if !retryable {
+                        return Err(err);
                     }
+                    (err, wait)
                 }
-            }
+                Err(e) if RetryPolicy::is_retryable_error(&e) => (e.into(), None),
+                Err(e) => return Err(e.into()),
+            };
+            let Some(delay) = self.retry.delay(attempt, wait) else {
+                return Err(err);
+            };
+            warn!("{}; retry {} in {}ms", err, attempt + 1, delay.as_millis());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
-        
-        Err(ApiError::Http(last_error.unwrap()))
-    }
-
-    pub async fn get_iss(&self) -> Result<Value, ApiError> {
-        self.get_with_retry(&self.config.where_iss_url).await
     }
 
-    pub async fn get_osdr(&self) -> Result<Value, ApiError> {
-        let mut url = self.config.nasa_api_url.clone();
-        if !self.config.nasa_api_key.is_empty() {
-            url = format!("{}&api_key={}", url, self.config.nasa_api_key);
+    // Документ OSDR API: страница каталога (NASA_API_URL, ссылки из ответа) или карточка датасета
+    pub async fn get_osdr(&self, url: &str) -> Result<Value, ApiError> {
+        let mut url = url.to_string();
+        // ключ только для адресов OSDR API из конфигурации, не для ссылок на чужие хосты
+        let own = same_origin(&url, &self.config.nasa_api_url)
+            || same_origin(&url, &self.config.osdr_detail_url_template);
+        if own && !self.config.nasa_api_key.is_empty() && !url.contains("api_key=") {
+            url = with_query_param(&url, "api_key", &self.config.nasa_api_key);
         }
         self.get_with_retry(&url).await
     }
@@ -116,3 +237,123 @@ fn last_days(n: i64) -> (String, String) {
     (from.to_string(), to.to_string())
 }
 
+
+// Схема, хост и порт совпадают
+pub fn same_origin(a: &str, b: &str) -> bool {
+    match (reqwest::Url::parse(a), reqwest::Url::parse(b)) {
+        (Ok(a), Ok(b)) => {
+            a.scheme() == b.scheme()
+                && a.host_str() == b.host_str()
+                && a.port_or_known_default() == b.port_or_known_default()
+        }
+        _ => false,
+    }
+}
+
+// Заменяет (или добавляет) параметр query string; кривой URL возвращается как есть
+pub fn with_query_param(url: &str, key: &str, value: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| k != key)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(key, value);
+    parsed.into()
+}
+
+// параметры, значения которых не должны попадать в ошибки, логи и sync_runs
+const SECRET_PARAMS: &[&str] = &["api_key"];
+
+pub fn redact_url(url: &mut reqwest::Url) {
+    if !url.query_pairs().any(|(k, _)| SECRET_PARAMS.contains(&k.as_ref())) {
+        return;
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let v = if SECRET_PARAMS.contains(&k.as_ref()) { "REDACTED".into() } else { v };
+            (k.into_owned(), v.into_owned())
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+pub fn redacted(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            redact_url(&mut parsed);
+            parsed.into()
+        }
+        Err(_) => url.split('?').next().unwrap_or(url).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { max_retries: 10, base_delay: Duration::from_secs(1), max_delay: Duration::from_secs(30) }
+    }
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        for code in [400, 401, 403, 404] {
+            assert!(!RetryPolicy::is_retryable_status(StatusCode::from_u16(code).unwrap()), "{code}");
+        }
+        for code in [408, 429, 500, 502, 503, 504] {
+            assert!(RetryPolicy::is_retryable_status(StatusCode::from_u16(code).unwrap()), "{code}");
+        }
+    }
+
+    #[test]
+    fn delay_is_jittered_under_capped_backoff() {
+        let p = policy();
+        for attempt in 0..p.max_retries {
+            let cap = Duration::from_secs((1u64 << attempt).min(30));
+            for _ in 0..200 {
+                assert!(p.delay(attempt, None).unwrap() <= cap, "attempt {attempt}");
+            }
+        }
+        // full jitter: паузы разные, а не всегда cap
+        let samples: Vec<_> = (0..50).map(|_| p.delay(3, None).unwrap()).collect();
+        assert!(samples.iter().any(|d| *d != samples[0]));
+        assert_eq!(p.delay(p.max_retries, None), None);
+    }
+
+    #[test]
+    fn retry_after_beyond_max_delay_gives_up() {
+        let p = policy();
+        assert_eq!(p.delay(0, Some(Duration::from_secs(30))), Some(Duration::from_secs(30)));
+        assert_eq!(p.delay(0, Some(Duration::from_secs(31))), None);
+        assert_eq!(p.delay(p.max_retries, Some(Duration::from_secs(1))), None);
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        assert_eq!(retry_after(&headers("120")), Some(Duration::from_secs(120)));
+        assert_eq!(retry_after(&headers(" 0 ")), Some(Duration::ZERO));
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+
+        let at = chrono::Utc::now() + chrono::Duration::seconds(90);
+        let wait = retry_after(&headers(&at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())).unwrap();
+        assert!(wait > Duration::from_secs(85) && wait <= Duration::from_secs(90), "{wait:?}");
+        // дата в прошлом - повторять сразу
+        assert_eq!(retry_after(&headers("Sun, 06 Nov 1994 08:49:37 GMT")), Some(Duration::ZERO));
+    }
+}
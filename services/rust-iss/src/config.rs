@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use crate::lock::LockMode;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -6,12 +7,55 @@ pub struct Config {
     pub nasa_api_url: String,
     pub nasa_api_key: String,
     pub where_iss_url: String,
+    pub where_iss_tle_url: String,
+    pub extra_satellites: Vec<(i32, String)>,
+    pub sat_tle_url_template: String,
+    pub geo_countries_path: String,
+    pub geo_marine_path: String,
+    pub geo_required: bool,
+    pub alert_radius_km: f64,
+    pub alert_webhook_url: Option<String>,
+    pub alert_webhook_allowlist: Vec<reqwest::Url>,
+    pub osdr_list_limit: i64,
+    pub osdr_since_param: String,
+    pub osdr_full_every_hours: i64,
+    pub osdr_max_pages: u32,
+    pub osdr_detail_url_template: String,
+    pub sync_runs_keep_days: i64,
     pub fetch_intervals: FetchIntervals,
+    pub job_timing: HashMap<String, JobTiming>,
+    pub instance_id: String,
+    pub lock_mode: LockMode,
+    pub lock_lease_seconds: u64,
+    pub leader_lease_seconds: u64,
+    pub jobs_admin_token: Option<String>,
+    pub shutdown_timeout_secs: u64,
     pub http_timeout_secs: u64,
     pub max_retries: u32,
     pub retry_delay_secs: u64,
+    pub retry_max_delay_secs: u64,
 }
 
+// Расписание задачи сверх интервала: cron (UTC), случайная добавка к каждому
+// ожиданию и задержка первого запуска
+#[derive(Clone, Debug, Default)]
+pub struct JobTiming {
+    pub cron: Option<CronExpr>,
+    pub jitter_secs: u64,
+    pub initial_delay_secs: u64,
+}
+
+// ключ задачи -> префикс переменных окружения (APOD_CRON, APOD_JITTER_SECONDS, ...)
+const JOB_ENV_PREFIXES: &[(&str, &str)] = &[
+    ("osdr", "OSDR"),
+    ("iss", "ISS"),
+    ("tle", "TLE"),
+    ("apod", "APOD"),
+    ("neo", "NEO"),
+    ("donki", "DONKI"),
+    ("spacex", "SPACEX"),
+];
+
 #[derive(Clone, Debug)]
 pub struct FetchIntervals {
     pub osdr: u64,
@@ -20,6 +64,7 @@ pub struct FetchIntervals {
     pub neo: u64,
     pub donki: u64,
     pub spacex: u64,
+    pub tle: u64,
 }
 
 impl FetchIntervals {
@@ -31,6 +76,7 @@ impl FetchIntervals {
             neo: 7200,
             donki: 3600,
             spacex: 3600,
+            tle: 21600,
         }
     }
 }
@@ -48,24 +94,213 @@ impl Config {
         let where_iss_url = std::env::var("WHERE_ISS_URL")
             .unwrap_or_else(|_| "https://api.wheretheiss.at/v1/satellites/25544".to_string());
 
+        let where_iss_tle_url = std::env::var("WHERE_ISS_TLE_URL")
+            .unwrap_or_else(|_| "https://api.wheretheiss.at/v1/satellites/25544/tles".to_string());
+
+        // "48274:CSS (TIANHE),20580:HST" - дополнительные спутники для реестра
+        let extra_satellites = std::env::var("EXTRA_SATELLITES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let (id, name) = item.split_once(':').unwrap_or((item, ""));
+                let id = id.trim().parse::<i32>().ok()?;
+                let name = if name.trim().is_empty() { id.to_string() } else { name.trim().to_string() };
+                Some((id, name))
+            })
+            .collect();
+
+        let sat_tle_url_template = std::env::var("SAT_TLE_URL_TEMPLATE")
+            .unwrap_or_else(|_| "https://celestrak.org/NORAD/elements/gp.php?CATNR={norad_id}&FORMAT=TLE".to_string());
+
+        // Natural Earth 1:110m из services/rust-iss/data, копируются в образ (см. Dockerfile)
+        let geo_countries_path = std::env::var("GEO_COUNTRIES_PATH")
+            .unwrap_or_else(|_| "data/ne_110m_admin_0_countries.geojson".to_string());
+        let geo_marine_path = std::env::var("GEO_MARINE_PATH")
+            .unwrap_or_else(|_| "data/ne_110m_geography_marine_polys.geojson".to_string());
+
+        // радиус по умолчанию для новых точек наблюдения; вебхук - для точек без своего
+        let alert_radius_km = std::env::var("ALERT_RADIUS_KM")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1000.0);
+        let alert_webhook_url = std::env::var("ALERT_WEBHOOK_URL").ok().filter(|s| !s.is_empty());
+        // префиксы адресов, допустимых как webhook_url точки наблюдения; пусто - только ALERT_WEBHOOK_URL
+        let alert_webhook_allowlist = std::env::var("ALERT_WEBHOOK_ALLOWLIST")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                reqwest::Url::parse(s).map_err(|e| anyhow::anyhow!("ALERT_WEBHOOK_ALLOWLIST: invalid url {}: {}", s, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // имя реплики в блокировках и pg_stat_activity; в Docker HOSTNAME - id контейнера
+        let instance_id = std::env::var("INSTANCE_ID")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| format!("rust_iss-{}", std::process::id()));
+        let lock_mode = match std::env::var("LOCK_MODE") {
+            Ok(s) => LockMode::parse(&s).ok_or_else(|| anyhow::anyhow!("LOCK_MODE must be session or lease, got {}", s))?,
+            Err(_) => LockMode::Session,
+        };
+
+        let d = FetchIntervals::new();
+
+        // JOB_JITTER_SECONDS / JOB_INITIAL_DELAY_SECONDS - значения для всех задач
+        let jitter = env_u64("JOB_JITTER_SECONDS", 0);
+        let initial_delay = env_u64("JOB_INITIAL_DELAY_SECONDS", 0);
+        let mut job_timing = HashMap::new();
+        for (key, prefix) in JOB_ENV_PREFIXES {
+            let cron = match std::env::var(format!("{}_CRON", prefix)).ok().filter(|s| !s.trim().is_empty()) {
+                Some(expr) => Some(parse_cron(&expr).map_err(|e| anyhow::anyhow!("{}_CRON: {}", prefix, e))?),
+                None => None,
+            };
+            job_timing.insert(
+                key.to_string(),
+                JobTiming {
+                    cron,
+                    jitter_secs: env_u64(&format!("{}_JITTER_SECONDS", prefix), jitter),
+                    initial_delay_secs: env_u64(&format!("{}_INITIAL_DELAY_SECONDS", prefix), initial_delay),
+                },
+            );
+        }
+
         Ok(Config {
             database_url,
             nasa_api_url,
             nasa_api_key,
             where_iss_url,
+            where_iss_tle_url,
+            extra_satellites,
+            sat_tle_url_template,
+            geo_countries_path,
+            geo_marine_path,
+            // без границ регионов - ошибка запуска, а не работа без /iss/over
+            geo_required: matches!(std::env::var("GEO_REQUIRED").as_deref(), Ok("1" | "true")),
+            alert_radius_km,
+            alert_webhook_url,
+            alert_webhook_allowlist,
+            // размер страницы /osdr/list, если ?limit= не передан
+            osdr_list_limit: std::env::var("OSDR_LIST_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(20),
+            // инкрементальная синхронизация: параметр "обновлено с" и период полного обхода
+            osdr_since_param: std::env::var("OSDR_SINCE_PARAM").unwrap_or_else(|_| "updated_since".to_string()),
+            osdr_full_every_hours: env_u64("OSDR_FULL_EVERY_HOURS", 24) as i64,
+            osdr_max_pages: env_u32("OSDR_MAX_PAGES", 500),
+            // карточка датасета, если в записи каталога нет REST_URL
+            osdr_detail_url_template: std::env::var("OSDR_DETAIL_URL_TEMPLATE").unwrap_or_else(|_| {
+                "https://visualization.osdr.nasa.gov/biodata/api/v2/dataset/{dataset_id}/?format=json".to_string()
+            }),
+            // сколько дней хранить журнал запусков задач
+            sync_runs_keep_days: env_u64("SYNC_RUNS_KEEP_DAYS", 30) as i64,
             fetch_intervals: FetchIntervals {
-                osdr: env_u64("FETCH_EVERY_SECONDS", 600),
-                iss: env_u64("ISS_EVERY_SECONDS", 120),
-                apod: env_u64("APOD_EVERY_SECONDS", 43200),
-                neo: env_u64("NEO_EVERY_SECONDS", 7200),
-                donki: env_u64("DONKI_EVERY_SECONDS", 3600),
-                spacex: env_u64("SPACEX_EVERY_SECONDS", 3600),
+                osdr: env_u64("FETCH_EVERY_SECONDS", d.osdr),
+                iss: env_u64("ISS_EVERY_SECONDS", d.iss),
+                apod: env_u64("APOD_EVERY_SECONDS", d.apod),
+                neo: env_u64("NEO_EVERY_SECONDS", d.neo),
+                donki: env_u64("DONKI_EVERY_SECONDS", d.donki),
+                spacex: env_u64("SPACEX_EVERY_SECONDS", d.spacex),
+                tle: env_u64("TLE_EVERY_SECONDS", d.tle),
             },
+            job_timing,
+            instance_id,
+            lock_mode,
+            // lease: срок аренды, продлевается каждые ttl/3
+            lock_lease_seconds: env_u64("LOCK_LEASE_SECONDS", 60),
+            // аренда роли ведущей реплики; при сбое роль переходит через этот срок
+            leader_lease_seconds: env_u64("LEADER_LEASE_SECONDS", 30),
+            // токен для запуска, паузы и смены расписания через /jobs; без него они отключены
+            jobs_admin_token: std::env::var("JOBS_ADMIN_TOKEN").ok().filter(|s| !s.is_empty()),
+            // SIGTERM: сколько ждать текущие запуски задач, потом они прерываются
+            shutdown_timeout_secs: env_u64("SHUTDOWN_TIMEOUT_SECONDS", 30),
             http_timeout_secs: env_u64("HTTP_TIMEOUT_SECS", 30),
             max_retries: env_u32("MAX_RETRIES", 3),
+            // повторы: база экспоненциальной паузы и её предел (и предел Retry-After)
             retry_delay_secs: env_u64("RETRY_DELAY_SECS", 2),
+            retry_max_delay_secs: env_u64("RETRY_MAX_DELAY_SECS", 30),
         })
     }
+
+    pub fn job_timing(&self, key: &str) -> JobTiming {
+        self.job_timing.get(key).cloned().unwrap_or_default()
+    }
+}
+
+// cron-выражение в том виде, как его задал пользователь, и разобранное расписание;
+// наружу (GET /jobs) отдаётся expr: Display крейта показывает уже переведённые дни недели
+#[derive(Clone, Debug)]
+pub struct CronExpr {
+    pub expr: String,
+    pub schedule: cron::Schedule,
+}
+
+// Обычный 5-польный cron ("10 5 * * *") или с секундами (6-7 полей).
+// День недели - как в crontab (0 и 7 - воскресенье, 1-5 - пн-пт); крейт cron
+// нумерует с воскресенья = 1, поэтому числа переводятся в явный список.
+pub fn parse_cron(expr: &str) -> anyhow::Result<CronExpr> {
+    let original = expr.trim();
+    let mut fields: Vec<String> = expr.split_whitespace().map(str::to_string).collect();
+    if fields.len() == 5 {
+        fields.insert(0, "0".to_string());
+    }
+    if let Some(dow) = fields.get_mut(5) {
+        *dow = cron_weekdays(dow).map_err(|e| anyhow::anyhow!("invalid cron expression '{}': {}", original, e))?;
+    }
+    let schedule = fields
+        .join(" ")
+        .parse::<cron::Schedule>()
+        .map_err(|e| anyhow::anyhow!("invalid cron expression '{}': {}", original, e))?;
+    Ok(CronExpr { expr: original.to_string(), schedule })
+}
+
+// Числовые элементы поля дня недели (n, a-b, */s, a-b/s, a/s) - в номера крейта;
+// *, ? и имена (MON-FRI) без изменений
+fn cron_weekdays(field: &str) -> anyhow::Result<String> {
+    if field == "*" || field == "?" {
+        return Ok(field.to_string());
+    }
+    let mut days = Vec::new();
+    let mut names = Vec::new();
+    for item in field.split(',') {
+        if item.chars().all(|c| c.is_ascii_alphabetic() || c == '-') {
+            names.push(item.to_string());
+            continue;
+        }
+        let (range, step) = match item.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().map_err(|_| anyhow::anyhow!("bad step in '{}'", item))?),
+            None => (item, 1),
+        };
+        if step == 0 {
+            anyhow::bail!("bad step in '{}'", item);
+        }
+        let num = |s: &str| {
+            s.parse::<u32>()
+                .ok()
+                .filter(|n| *n <= 7)
+                .ok_or_else(|| anyhow::anyhow!("day of week must be 0-7 or a name, got '{}'", item))
+        };
+        let (from, to) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((a, b)) => (num(a)?, num(b)?),
+            // a/s - от a до конца недели
+            None if item.contains('/') => (num(range)?, 7),
+            None => {
+                let n = num(range)?;
+                (n, n)
+            }
+        };
+        if from > to {
+            anyhow::bail!("bad day-of-week range '{}'", item);
+        }
+        days.extend((from..=to).step_by(step as usize).map(|d| d % 7 + 1));
+    }
+    days.sort_unstable();
+    days.dedup();
+    let mut out: Vec<String> = days.iter().map(u32::to_string).collect();
+    out.extend(names);
+    Ok(out.join(","))
 }
 
 fn env_u64(k: &str, d: u64) -> u64 {
@@ -77,3 +312,54 @@ fn env_u32(k: &str, d: u32) -> u32 {
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Timelike, Utc, Weekday};
+
+    fn upcoming(expr: &str, n: usize) -> Vec<chrono::DateTime<Utc>> {
+        let from = Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap();
+        parse_cron(expr).unwrap().schedule.after(&from).take(n).collect()
+    }
+
+    #[test]
+    fn numeric_weekdays_follow_crontab() {
+        assert_eq!(cron_weekdays("0").unwrap(), "1");
+        assert_eq!(cron_weekdays("7").unwrap(), "1");
+        assert_eq!(cron_weekdays("1-5").unwrap(), "2,3,4,5,6");
+        assert_eq!(cron_weekdays("*/2").unwrap(), "1,3,5,7");
+        assert_eq!(cron_weekdays("MON-FRI").unwrap(), "MON-FRI");
+        assert_eq!(cron_weekdays("*").unwrap(), "*");
+        assert!(cron_weekdays("8").is_err());
+        assert!(cron_weekdays("5-1").is_err());
+    }
+
+    #[test]
+    fn weekday_names_and_numbers_agree() {
+        let days = |expr| upcoming(expr, 10).iter().map(|t| t.weekday()).collect::<Vec<_>>();
+        assert_eq!(days("0 9 * * 1-5"), days("0 9 * * MON-FRI"));
+        assert!(days("0 9 * * 1-5").iter().all(|d| !matches!(d, Weekday::Sat | Weekday::Sun)));
+        assert!(days("0 9 * * 0").iter().all(|d| *d == Weekday::Sun));
+        assert!(days("0 9 * * 7").iter().all(|d| *d == Weekday::Sun));
+        assert_eq!(days("0 9 * * */2")[..4], [Weekday::Sun, Weekday::Tue, Weekday::Thu, Weekday::Sat]);
+    }
+
+    #[test]
+    fn five_six_and_seven_fields() {
+        // 5 полей: секунды = 0
+        let t = upcoming("10 5 * * 1", 1)[0];
+        assert_eq!((t.weekday(), t.hour(), t.minute(), t.second()), (Weekday::Mon, 5, 10, 0));
+        // 6 полей: с секундами
+        let t = upcoming("30 10 5 * * 1", 1)[0];
+        assert_eq!((t.weekday(), t.hour(), t.minute(), t.second()), (Weekday::Mon, 5, 10, 30));
+        // 7 полей: с годом
+        let t = upcoming("0 10 5 * * 1 2030", 1)[0];
+        assert_eq!((t.year(), t.weekday(), t.hour(), t.minute()), (2030, Weekday::Mon, 5, 10));
+        assert!(parse_cron("10 5 * *").is_err());
+    }
+
+    #[test]
+    fn keeps_original_expression() {
+        assert_eq!(parse_cron("  10 5 * * 1 ").unwrap().expr, "10 5 * * 1");
+    }
+}
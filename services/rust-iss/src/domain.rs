@@ -1,13 +1,165 @@
-use chrono::{DateTime, Utc};
+use crate::error::ApiError;
+use crate::geo::RegionHit;
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub const ISS_NORAD_ID: i32 = 25544;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Satellite {
+    pub norad_id: i32,
+    pub name: String,
+    pub position_url: Option<String>,
+    pub tle_url: Option<String>,
+    pub fetch_every_seconds: i64,
+    pub tle_every_seconds: i64,
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IssLog {
     pub id: i64,
+    pub norad_id: i32,
     pub fetched_at: DateTime<Utc>,
     pub source_url: String,
     pub payload: Value,
+    pub position: Option<IssPosition>,
+    pub region: Option<RegionHit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    pub velocity_kmh: f64,
+    pub visibility: Option<String>,
+    pub footprint_km: Option<f64>,
+    pub solar_lat: Option<f64>,
+    pub solar_lon: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl IssPosition {
+    // Ответ wheretheiss.at: обязательные поля проверяются при записи
+    pub fn from_payload(v: &Value) -> Result<Self, ApiError> {
+        let req = |k: &str| {
+            num(&v[k]).ok_or_else(|| ApiError::Validation(format!("ISS payload: missing or invalid {}", k)))
+        };
+        let latitude = req("latitude")?;
+        let longitude = req("longitude")?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(ApiError::Validation("ISS payload: coordinates out of range".to_string()));
+        }
+        let timestamp = Utc
+            .timestamp_opt(req("timestamp")? as i64, 0)
+            .single()
+            .ok_or_else(|| ApiError::Validation("ISS payload: invalid timestamp".to_string()))?;
+
+        Ok(Self {
+            latitude,
+            longitude,
+            altitude_km: req("altitude")?,
+            velocity_kmh: req("velocity")?,
+            visibility: v["visibility"].as_str().map(|s| s.to_string()),
+            footprint_km: num(&v["footprint"]),
+            solar_lat: num(&v["solar_lat"]),
+            solar_lon: num(&v["solar_lon"]),
+            timestamp,
+        })
+    }
+}
+
+// Прореживание истории: каждая N-я строка или усреднение по date_trunc
+#[derive(Debug, Clone)]
+pub enum HistoryStep {
+    Every(i64),
+    Bucket(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssHistoryBucket {
+    pub bucket: DateTime<Utc>,
+    pub samples: i64,
+    pub last_id: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: Option<f64>,
+    pub velocity_kmh: Option<f64>,
+}
+
+// Смена региона под спутником; from_region = None - первая запись
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionCrossing {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    pub region: String,
+    pub kind: String,
+    pub from_region: Option<String>,
+}
+
+// Точка наблюдения для уведомлений о пролёте
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObserverLocation {
+    pub id: i64,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+    pub norad_id: i32,
+    pub webhook_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewObserverLocation {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: Option<f64>,
+    pub norad_id: Option<i32>,
+    pub webhook_url: Option<String>,
+}
+
+// Подспутниковая точка вошла в радиус точки наблюдения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProximityAlert {
+    pub id: i64,
+    pub location_id: i64,
+    pub location_name: String,
+    pub norad_id: i32,
+    pub log_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_km: f64,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub delivery_error: Option<String>,
+}
+
+// Событие шины позиций: новая запись в iss_fetch_log или точка интерполяции
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionEvent {
+    pub norad_id: i32,
+    pub interpolated: bool,
+    pub position: IssPosition,
+}
+
+impl IssPosition {
+    pub fn illumination(&self) -> crate::astro::Illumination {
+        crate::astro::illumination(self.latitude, self.longitude, self.altitude_km, self.timestamp)
+    }
+}
+
+pub fn num(v: &Value) -> Option<f64> {
+    if let Some(x) = v.as_f64() {
+        return Some(x);
+    }
+    if let Some(s) = v.as_str() {
+        return s.parse::<f64>().ok();
+    }
+    None
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,9 +170,202 @@ pub struct OsdrItem {
     pub status: Option<String>,
     pub updated_at: Option<DateTime<Utc>>,
     pub inserted_at: DateTime<Utc>,
+    pub removed_at: Option<DateTime<Utc>>,
+    pub raw_hash: Option<String>,
     pub raw: Value,
 }
 
+// Карточка датасета из OSDR API; перезапрашивается, когда raw в каталоге меняется
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrItemDetail {
+    pub dataset_id: String,
+    pub source_url: String,
+    pub fetched_at: DateTime<Utc>,
+    pub raw_hash: Option<String>,
+    pub files: Option<Value>,
+    pub assays: Option<Value>,
+    pub samples: Option<Value>,
+    pub factors: Option<Value>,
+    pub document: Value,
+}
+
+// Последняя неудачная загрузка карточки; до retry_at источник не запрашивается
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrDetailFailure {
+    pub dataset_id: String,
+    pub raw_hash: Option<String>,
+    pub error: String,
+    pub failures: i32,
+    pub failed_at: DateTime<Utc>,
+    pub retry_at: DateTime<Utc>,
+}
+
+// Редакция датасета; новая пишется только при изменении raw
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrItemVersion {
+    pub dataset_id: String,
+    pub version: i32,
+    pub raw_hash: String,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub raw: Value,
+    pub recorded_at: DateTime<Utc>,
+}
+
+// Состояние инкрементальной синхронизации источника
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncState {
+    pub source: String,
+    pub high_water: Option<DateTime<Utc>>,
+    pub last_full_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+// Запуск фоновой задачи; outcome: running | success | error | skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRun {
+    pub id: i64,
+    pub job: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub outcome: String,
+    pub error: Option<String>,
+    pub fetched: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub unchanged: i64,
+    pub upstream_ms: Option<i64>,
+}
+
+// Настройки задачи, изменённые через /jobs; переживают перезапуск
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSettings {
+    pub name: String,
+    pub paused: bool,
+    pub interval_seconds: Option<i64>,
+    pub cron: Option<String>,
+    pub jitter_seconds: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// PATCH /jobs/:name: интервал или cron (одно из двух) и/или jitter
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobPatch {
+    pub interval_seconds: Option<i64>,
+    pub cron: Option<String>,
+    pub jitter_seconds: Option<i64>,
+}
+
+// Счётчики, которые задача отдаёт планировщику
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunStats {
+    pub fetched: u64,
+    pub inserted: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+}
+
+impl RunStats {
+    // Один ответ источника: новые данные или повтор прошлого ответа
+    pub fn single(inserted: bool) -> Self {
+        Self {
+            fetched: 1,
+            inserted: inserted as u64,
+            unchanged: !inserted as u64,
+            ..Self::default()
+        }
+    }
+
+    pub fn add(self, other: RunStats) -> Self {
+        Self {
+            fetched: self.fetched + other.fetched,
+            inserted: self.inserted + other.inserted,
+            updated: self.updated + other.updated,
+            unchanged: self.unchanged + other.unchanged,
+        }
+    }
+}
+
+impl From<&OsdrSyncStats> for RunStats {
+    fn from(s: &OsdrSyncStats) -> Self {
+        Self {
+            fetched: s.fetched,
+            inserted: s.inserted,
+            updated: s.updated,
+            unchanged: s.unchanged,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OsdrSyncStats {
+    pub full: bool,
+    pub since: Option<DateTime<Utc>>,
+    pub pages: u32,
+    pub fetched: u64,
+    pub inserted: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+    pub removed: u64,
+    pub restored: u64,
+    pub high_water: Option<DateTime<Utc>>,
+}
+
+// Результат /osdr/search: подсветка совпадений - <mark>...</mark>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsdrSearchHit {
+    pub id: i64,
+    pub dataset_id: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdrSort {
+    UpdatedAt,
+    Title,
+    InsertedAt,
+}
+
+impl OsdrSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "updated_at" => Some(Self::UpdatedAt),
+            "title" => Some(Self::Title),
+            "inserted_at" => Some(Self::InsertedAt),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UpdatedAt => "updated_at",
+            Self::Title => "title",
+            Self::InsertedAt => "inserted_at",
+        }
+    }
+}
+
+// Фильтры и порядок /osdr/list; after - ключ сортировки и id последней строки
+#[derive(Debug, Clone)]
+pub struct OsdrListQuery {
+    pub status: Option<String>,
+    pub updated_since: Option<DateTime<Utc>>,
+    pub q: Option<String>,
+    pub sort: OsdrSort,
+    pub descending: bool,
+    pub limit: i64,
+    pub offset: i64,
+    pub after: Option<(String, i64)>,
+    // None - все, false - активные, true - пропавшие из OSDR
+    pub removed: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpaceCache {
     pub id: i64,
@@ -43,5 +388,72 @@ pub struct IssTrend {
     pub to_lon: Option<f64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssTrendStats {
+    pub window_sec: i64,
+    pub from_time: Option<DateTime<Utc>>,
+    pub to_time: Option<DateTime<Utc>>,
+    pub samples: usize,
+    pub gaps: usize,
+    pub max_gap_sec: f64,
+    pub movement: bool,
+    pub distance_km: f64,
+    pub mean_velocity_kmh: Option<f64>,
+    pub max_velocity_kmh: Option<f64>,
+    pub reported_mean_velocity_kmh: Option<f64>,
+    pub velocity_deviation_pct: Option<f64>,
+    pub altitude_min_km: Option<f64>,
+    pub altitude_max_km: Option<f64>,
+    pub altitude_mean_km: Option<f64>,
+}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssTle {
+    pub id: i64,
+    pub fetched_at: DateTime<Utc>,
+    pub source_url: String,
+    pub name: Option<String>,
+    pub line1: String,
+    pub line2: String,
+    pub epoch: DateTime<Utc>,
+}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssOrbitState {
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    pub velocity_kmh: f64,
+    pub tle_epoch: DateTime<Utc>,
+    pub tle_age_hours: f64,
+    pub observed: Option<IssObservedSample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssObservedSample {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_km: f64,
+    pub velocity_kmh: f64,
+    pub delta_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssPass {
+    pub rise_time: DateTime<Utc>,
+    pub rise_azimuth: f64,
+    pub culmination_time: DateTime<Utc>,
+    pub culmination_azimuth: f64,
+    pub culmination_range_km: f64,
+    pub max_elevation: f64,
+    pub set_time: DateTime<Utc>,
+    pub set_azimuth: f64,
+    pub duration_sec: i64,
+    // спутник освещён, у наблюдателя ночь; from/to - границы видимой части
+    pub visible: bool,
+    pub visible_from: Option<DateTime<Utc>>,
+    pub visible_to: Option<DateTime<Utc>>,
+}
@@ -11,8 +11,12 @@ use uuid::Uuid;
 pub enum ApiError {
     Database(sqlx::Error),
     Http(reqwest::Error),
+    Upstream(UpstreamError),
     Validation(String),
     NotFound(String),
+    // операция уже выполняется (например, задача под блокировкой)
+    Conflict(String),
+    Forbidden(String),
     Internal(String),
 }
 
@@ -21,8 +25,11 @@ impl fmt::Display for ApiError {
         match self {
             ApiError::Database(e) => write!(f, "Database error: {}", e),
             ApiError::Http(e) => write!(f, "HTTP error: {}", e),
+            ApiError::Upstream(e) => write!(f, "{}", e),
             ApiError::Validation(msg) => write!(f, "Validation error: {}", msg),
             ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            ApiError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            ApiError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -30,6 +37,29 @@ impl fmt::Display for ApiError {
 
 impl std::error::Error for ApiError {}
 
+// Ответ источника с неуспешным статусом: адрес без query (там бывает api_key)
+// и начало тела ответа для диагностики
+#[derive(Debug, Clone)]
+pub struct UpstreamError {
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+    pub attempts: u32,
+}
+
+impl fmt::Display for UpstreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "upstream {} returned {}", self.url, self.status)?;
+        if self.attempts > 1 {
+            write!(f, " after {} attempts", self.attempts)?;
+        }
+        if !self.body.is_empty() {
+            write!(f, ": {}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub ok: bool,
@@ -75,6 +105,15 @@ impl IntoResponse for ApiError {
                 };
                 (StatusCode::OK, code, format!("External API error: {}", e))
             },
+            ApiError::Upstream(e) => {
+                let code = match e.status {
+                    403 => "UPSTREAM_403".to_string(),
+                    404 => "UPSTREAM_404".to_string(),
+                    429 => "UPSTREAM_429".to_string(),
+                    _ => "UPSTREAM_ERROR".to_string(),
+                };
+                (StatusCode::OK, code, format!("External API error: {}", e))
+            },
             ApiError::Validation(msg) => (
                 StatusCode::OK,
                 "VALIDATION_ERROR".to_string(),
@@ -85,6 +124,16 @@ impl IntoResponse for ApiError {
                 "NOT_FOUND".to_string(),
                 msg,
             ),
+            ApiError::Conflict(msg) => (
+                StatusCode::OK,
+                "CONFLICT".to_string(),
+                msg,
+            ),
+            ApiError::Forbidden(msg) => (
+                StatusCode::OK,
+                "FORBIDDEN".to_string(),
+                msg,
+            ),
             ApiError::Internal(msg) => (
                 StatusCode::OK,
                 "INTERNAL_ERROR".to_string(),
@@ -112,8 +161,12 @@ impl From<sqlx::Error> for ApiError {
     }
 }
 
+// URL ошибки reqwest попадает в ответ API и sync_runs - без api_key
 impl From<reqwest::Error> for ApiError {
-    fn from(err: reqwest::Error) -> Self {
+    fn from(mut err: reqwest::Error) -> Self {
+        if let Some(url) = err.url_mut() {
+            crate::clients::redact_url(url);
+        }
         ApiError::Http(err)
     }
 }
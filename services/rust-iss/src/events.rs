@@ -0,0 +1,78 @@
+use crate::app_state::AppState;
+use crate::domain::PositionEvent;
+use sqlx::postgres::{PgConnectOptions, PgListener, PgPoolOptions};
+use sqlx::ConnectOptions;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+// Уведомления между репликами через LISTEN/NOTIFY.
+// Позиции: опрос выполняет только ведущая реплика (IssRepo::notify_position),
+// а подписчики /iss/stream есть на всех - каждая раздаёт события в свой broadcast.
+// Настройки задач: пауза и расписание, изменённые через /jobs на любой реплике
+// (JobSettingsRepo::notify), перечитываются из job_settings.
+
+pub const POSITIONS_CHANNEL: &str = "rust_iss_positions";
+pub const JOB_SETTINGS_CHANNEL: &str = "rust_iss_job_settings";
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// Позиции за время переподключения теряются (стримы получат следующий опрос),
+// настройки задач после подключения перечитываются целиком
+pub async fn relay(state: AppState) {
+    loop {
+        tokio::select! {
+            res = listen(&state) => {
+                if let Err(e) = res {
+                    error!("notification listener error: {}", e);
+                }
+            }
+            _ = state.shutdown.cancelled() => return,
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+            _ = state.shutdown.cancelled() => return,
+        }
+    }
+}
+
+// Отдельное соединение, чтобы LISTEN не занимал соединение общего пула
+async fn listen(state: &AppState) -> anyhow::Result<()> {
+    let options = state
+        .config
+        .database_url
+        .parse::<PgConnectOptions>()?
+        .application_name(&format!("rust_iss {} listen", state.config.instance_id))
+        .disable_statement_logging();
+    let pool = PgPoolOptions::new().max_connections(1).connect_with(options).await?;
+    let mut listener = PgListener::connect_with(&pool).await?;
+    listener.listen_all([POSITIONS_CHANNEL, JOB_SETTINGS_CHANNEL]).await?;
+    info!("listening for {} and {}", POSITIONS_CHANNEL, JOB_SETTINGS_CHANNEL);
+    reload_jobs(state).await;
+    loop {
+        // recv() переподключился бы сам, и NOTIFY за время обрыва пропали бы молча;
+        // вместо этого выходим, и relay подключится заново с перечитыванием настроек
+        let Some(notification) = listener.try_recv().await? else {
+            anyhow::bail!("notification connection lost");
+        };
+        match notification.channel() {
+            POSITIONS_CHANNEL => match serde_json::from_str::<PositionEvent>(notification.payload()) {
+                // нет подписчиков - не ошибка
+                Ok(ev) => {
+                    let _ = state.events.send(ev);
+                }
+                Err(e) => warn!("bad position event payload: {}", e),
+            },
+            JOB_SETTINGS_CHANNEL => {
+                info!("job settings changed: {}", notification.payload());
+                reload_jobs(state).await;
+            }
+            other => warn!("unexpected notification channel {}", other),
+        }
+    }
+}
+
+async fn reload_jobs(state: &AppState) {
+    if let Err(e) = state.jobs.reload(&state.pool).await {
+        error!("job settings reload error: {}", e);
+    }
+}
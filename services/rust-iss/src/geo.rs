@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+// Обратное геокодирование без внешних сервисов: полигоны Natural Earth
+// (страны и морские районы) в памяти, отбор кандидатов по сетке 10°x10°
+// и проверка точки в полигоне лучом.
+
+const CELL_DEG: f64 = 10.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionHit {
+    pub name: String,
+    pub kind: String,
+}
+
+struct Region {
+    name: String,
+    kind: &'static str,
+    bbox: [f64; 4],
+    // полигоны: первое кольцо внешнее, остальные - дыры; точки [lon, lat]
+    polygons: Vec<Vec<Vec<[f64; 2]>>>,
+}
+
+pub struct RegionIndex {
+    regions: Vec<Region>,
+    grid: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl RegionIndex {
+    pub fn empty() -> Self {
+        Self { regions: Vec::new(), grid: HashMap::new() }
+    }
+
+    // Страны проверяются раньше морей: береговые полигоны морей грубее
+    pub fn load(countries: &Path, marine: &Path) -> anyhow::Result<Self> {
+        let mut index = Self::empty();
+        index.add_file(countries, "country")?;
+        if marine.exists() {
+            index.add_file(marine, "water")?;
+        } else {
+            warn!("marine polygons not found at {}: water regions will be reported as ocean", marine.display());
+        }
+        Ok(index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    // None - индекс не загружен; точка вне всех полигонов считается океаном
+    pub fn locate(&self, lat: f64, lon: f64) -> Option<RegionHit> {
+        if self.is_empty() {
+            return None;
+        }
+        let candidates = self.grid.get(&cell(lon, lat)).map(Vec::as_slice).unwrap_or(&[]);
+        let hit = candidates
+            .iter()
+            .map(|&i| &self.regions[i])
+            .find(|r| {
+                lon >= r.bbox[0]
+                    && lat >= r.bbox[1]
+                    && lon <= r.bbox[2]
+                    && lat <= r.bbox[3]
+                    && r.polygons.iter().any(|p| polygon_contains(p, lon, lat))
+            });
+        Some(match hit {
+            Some(r) => RegionHit { name: r.name.clone(), kind: r.kind.to_string() },
+            None => RegionHit { name: "Ocean".to_string(), kind: "water".to_string() },
+        })
+    }
+
+    fn add_file(&mut self, path: &Path, kind: &'static str) -> anyhow::Result<()> {
+        let json: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+        let features = json["features"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("{}: not a FeatureCollection", path.display()))?;
+
+        for f in features {
+            let props = &f["properties"];
+            let Some(name) = ["NAME", "ADMIN", "name", "NAME_EN"]
+                .iter()
+                .find_map(|k| props[*k].as_str().filter(|s| !s.is_empty()))
+            else {
+                continue;
+            };
+            let polygons = match f["geometry"]["type"].as_str() {
+                Some("Polygon") => vec![rings(&f["geometry"]["coordinates"])],
+                Some("MultiPolygon") => f["geometry"]["coordinates"]
+                    .as_array()
+                    .map(|ps| ps.iter().map(rings).collect())
+                    .unwrap_or_default(),
+                _ => continue,
+            };
+            let points = || polygons.iter().flat_map(|p| p.first()).flatten();
+            if points().next().is_none() {
+                continue;
+            }
+            let bbox = points().fold([180.0f64, 90.0, -180.0, -90.0], |b, p| {
+                [b[0].min(p[0]), b[1].min(p[1]), b[2].max(p[0]), b[3].max(p[1])]
+            });
+
+            let idx = self.regions.len();
+            let (c0, c1) = (cell(bbox[0], bbox[1]), cell(bbox[2], bbox[3]));
+            for x in c0.0..=c1.0 {
+                for y in c0.1..=c1.1 {
+                    self.grid.entry((x, y)).or_default().push(idx);
+                }
+            }
+            self.regions.push(Region { name: name.to_string(), kind, bbox, polygons });
+        }
+        Ok(())
+    }
+}
+
+fn cell(lon: f64, lat: f64) -> (i32, i32) {
+    ((lon / CELL_DEG).floor() as i32, (lat / CELL_DEG).floor() as i32)
+}
+
+fn rings(v: &Value) -> Vec<Vec<[f64; 2]>> {
+    v.as_array()
+        .map(|rs| {
+            rs.iter()
+                .map(|r| {
+                    r.as_array()
+                        .map(|pts| {
+                            pts.iter()
+                                .filter_map(|p| Some([p[0].as_f64()?, p[1].as_f64()?]))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn polygon_contains(rings: &[Vec<[f64; 2]>], x: f64, y: f64) -> bool {
+    match rings.split_first() {
+        Some((outer, holes)) => ring_contains(outer, x, y) && !holes.iter().any(|h| ring_contains(h, x, y)),
+        None => false,
+    }
+}
+
+fn ring_contains(ring: &[[f64; 2]], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let (pi, pj) = (ring[i], ring[j]);
+        if (pi[1] > y) != (pj[1] > y) && x < (pj[0] - pi[0]) * (y - pi[1]) / (pj[1] - pi[1]) + pi[0] {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
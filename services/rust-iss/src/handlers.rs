@@ -1,31 +1,73 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::rejection::JsonRejection;
 use axum::extract::{Path, Query, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
 use axum::Json;
 use crate::app_state::AppState;
+use crate::astro;
+use crate::domain::{HistoryStep, JobPatch, NewObserverLocation, OsdrListQuery, OsdrSort, ISS_NORAD_ID};
 use crate::error::{ApiError, SuccessResponse};
-use crate::services::{IssService, OsdrService, SpaceCacheService};
-use chrono::Utc;
+use crate::jobs::with_job_lock;
+use crate::services::{decode_cursor, AlertService, IssService, JobService, OsdrService, PositionStream, SpaceCacheService};
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::convert::Infallible;
+use tokio_util::sync::CancellationToken;
 
-pub async fn health() -> Json<Value> {
+pub async fn health(State(state): State<AppState>) -> Json<Value> {
+    // health отвечает и без БД; тогда ведущая реплика неизвестна
+    let status = tokio::time::timeout(std::time::Duration::from_secs(2), state.leader.status())
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("database timeout")));
+    let leadership = match status {
+        Ok(status) => serde_json::to_value(status).unwrap_or(Value::Null),
+        Err(e) => serde_json::json!({
+            "instance_id": state.config.instance_id,
+            "is_leader": state.leader.is_leader(),
+            "error": e.to_string()
+        }),
+    };
     Json(serde_json::json!({
         "ok": true,
         "status": "ok",
-        "now": Utc::now()
+        "now": Utc::now(),
+        "leadership": leadership,
+        // 0 - границы регионов не загружены, /iss/over не работает
+        "region_polygons": state.regions.len()
     }))
 }
 
-pub async fn last_iss(
+pub async fn satellites(
     State(state): State<AppState>,
 ) -> Result<Json<SuccessResponse<Value>>, ApiError> {
     let service = IssService::new(state.pool.clone(), state.http_client.clone());
+    let sats = service.list_satellites().await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "items": sats
+    }))))
+}
+
+pub async fn sat_last(
+    Path(norad_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
     let log = service.get_last().await?;
     
     if let Some(log) = log {
         Ok(Json(SuccessResponse::new(serde_json::json!({
             "id": log.id,
+            "norad_id": log.norad_id,
             "fetched_at": log.fetched_at,
             "source_url": log.source_url,
+            "illumination": log.position.as_ref().map(|p| p.illumination()),
+            "region": log.region,
+            "position": log.position,
             "payload": log.payload
         }))))
     } else {
@@ -35,42 +77,446 @@ pub async fn last_iss(
     }
 }
 
+pub async fn sat_fetch(
+    Path(norad_id): Path<i32>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id)
+        .with_regions(state.regions.clone());
+    let sat = service.satellite().await?;
+    service.fetch_and_store(&sat).await?;
+    AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config)
+        .check(norad_id)
+        .await?;
+    sat_last(Path(norad_id), State(state)).await
+}
+
+pub async fn sat_trend(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+
+    // без window - прежнее сравнение двух последних записей
+    let Some(window) = q.get("window").filter(|s| !s.trim().is_empty()) else {
+        let trend = service.get_trend().await?;
+        return Ok(Json(SuccessResponse::new(serde_json::to_value(trend).unwrap_or_default())));
+    };
+
+    let window = parse_window(window)?;
+    if window > chrono::Duration::days(7) {
+        return Err(ApiError::Validation("window is limited to 7 days".to_string()));
+    }
+    let sat = service.satellite().await?;
+    let gap_sec = query_param::<f64>(&q, "gap_sec")?
+        .unwrap_or(sat.fetch_every_seconds as f64 * 2.0);
+    if !gap_sec.is_finite() || gap_sec <= 0.0 {
+        return Err(ApiError::Validation("gap_sec must be a positive number".to_string()));
+    }
+
+    let stats = service.get_trend_window(window, gap_sec).await?;
+    Ok(Json(SuccessResponse::new(serde_json::to_value(stats).unwrap_or_default())))
+}
+
+pub async fn sat_position(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<crate::domain::IssOrbitState>>, ApiError> {
+    let at = query_time(&q, "at")?.unwrap_or_else(Utc::now);
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+    let pos = service.position_at(at).await?;
+    Ok(Json(SuccessResponse::new(pos)))
+}
+
+pub async fn sat_passes(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let lat = query_param::<f64>(&q, "lat")?
+        .ok_or_else(|| ApiError::Validation("lat is required".to_string()))?;
+    let lon = query_param::<f64>(&q, "lon")?
+        .ok_or_else(|| ApiError::Validation("lon is required".to_string()))?;
+    let alt_m = query_param::<f64>(&q, "alt")?.unwrap_or(0.0);
+    let days = query_param::<i64>(&q, "days")?.unwrap_or(3);
+    let min_el = query_param::<f64>(&q, "min_el")?.unwrap_or(10.0);
+
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(ApiError::Validation("lat/lon out of range".to_string()));
+    }
+    if !(1..=10).contains(&days) {
+        return Err(ApiError::Validation("days must be between 1 and 10".to_string()));
+    }
+
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+    let only_visible = matches!(q.get("visible").map(String::as_str), Some("1" | "true"));
+    let mut passes = service.passes(lat, lon, alt_m / 1000.0, Utc::now(), days, min_el).await?;
+    if only_visible {
+        passes.retain(|p| p.visible);
+    }
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "norad_id": norad_id,
+        "observer": { "lat": lat, "lon": lon, "alt": alt_m },
+        "days": days,
+        "min_elevation": min_el,
+        "visible_only": only_visible,
+        "passes": passes
+    }))))
+}
+
+pub async fn sat_track(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let to = query_time(&q, "to")?.unwrap_or_else(Utc::now);
+    let from = query_time(&q, "from")?.unwrap_or(to - chrono::Duration::minutes(90));
+    let predict = query_param::<i64>(&q, "predict")?.unwrap_or(0);
+
+    if from >= to {
+        return Err(ApiError::Validation("from must be before to".to_string()));
+    }
+    if to - from > chrono::Duration::days(7) {
+        return Err(ApiError::Validation("track range is limited to 7 days".to_string()));
+    }
+    if !(0..=1440).contains(&predict) {
+        return Err(ApiError::Validation("predict must be between 0 and 1440 minutes".to_string()));
+    }
+
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+    let track = service.ground_track(from, to, predict).await?;
+    Ok(Json(SuccessResponse::new(track)))
+}
+
+pub async fn sat_history(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let to = query_time(&q, "to")?.unwrap_or_else(Utc::now);
+    let from = query_time(&q, "from")?.unwrap_or(to - chrono::Duration::hours(24));
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(500);
+    let cursor = query_param::<i64>(&q, "cursor")?.unwrap_or(0);
+
+    if from >= to {
+        return Err(ApiError::Validation("from must be before to".to_string()));
+    }
+    if !(1..=5000).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 5000".to_string()));
+    }
+
+    let step = match q.get("step").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        None => HistoryStep::Every(1),
+        Some(s @ ("minute" | "hour" | "day" | "week")) => HistoryStep::Bucket(s.to_string()),
+        Some(s) => match s.parse::<i64>() {
+            Ok(n) if n >= 1 => HistoryStep::Every(n),
+            _ => {
+                return Err(ApiError::Validation(
+                    "step must be a positive integer or one of minute/hour/day/week".to_string(),
+                ))
+            }
+        },
+    };
+
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+    let page = service.history(from, to, cursor, limit, &step).await?;
+    Ok(Json(SuccessResponse::new(page)))
+}
+
+pub async fn sat_over(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let to = query_time(&q, "to")?.unwrap_or_else(Utc::now);
+    let from = query_time(&q, "from")?.unwrap_or(to - chrono::Duration::hours(24));
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(50);
+
+    if from >= to {
+        return Err(ApiError::Validation("from must be before to".to_string()));
+    }
+    if !(1..=1000).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 1000".to_string()));
+    }
+
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id)
+        .with_regions(state.regions.clone());
+    let over = service.over(from, to, limit).await?;
+    Ok(Json(SuccessResponse::new(over)))
+}
+
+pub async fn sat_stream_sse(
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = position_stream(&state, norad_id, &q);
+    let events = futures_util::stream::unfold(stream, |mut stream| async move {
+        let ev = stream.next().await?;
+        let event = Event::default()
+            .event("position")
+            .json_data(&ev)
+            .unwrap_or_else(|_| Event::default().comment("serialization error"));
+        Some((Ok(event), stream))
+    });
+    // при остановке сервиса поток завершается, иначе graceful shutdown его ждёт
+    Sse::new(events.take_until(state.shutdown.clone().cancelled_owned())).keep_alive(KeepAlive::default())
+}
+
+pub async fn sat_stream_ws(
+    ws: WebSocketUpgrade,
+    Path(norad_id): Path<i32>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Response {
+    let stream = position_stream(&state, norad_id, &q);
+    let shutdown = state.shutdown.clone();
+    ws.on_upgrade(move |socket| push_positions(socket, stream, shutdown))
+}
+
+async fn push_positions(mut socket: WebSocket, mut stream: PositionStream, shutdown: CancellationToken) {
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            ev = stream.next() => {
+                let Some(ev) = ev else { break };
+                let Ok(text) = serde_json::to_string(&ev) else { continue };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn position_stream(state: &AppState, norad_id: i32, q: &HashMap<String, String>) -> PositionStream {
+    let interpolate = matches!(q.get("interpolate").map(String::as_str), Some("1" | "true"));
+    let service = IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id);
+    PositionStream::new(service, state.events.subscribe(), interpolate)
+}
+
+// Исторические маршруты МКС - псевдонимы /sat/25544/...
+
+pub async fn last_iss(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_last(Path(ISS_NORAD_ID), State(state)).await
+}
+
 pub async fn trigger_iss(
     State(state): State<AppState>,
 ) -> Result<Json<SuccessResponse<Value>>, ApiError> {
-    let service = IssService::new(state.pool.clone(), state.http_client.clone());
-    service.fetch_and_store(&state.config.where_iss_url).await?;
-    last_iss(State(state)).await
+    sat_fetch(Path(ISS_NORAD_ID), State(state)).await
 }
 
 pub async fn iss_trend(
+    Query(q): Query<HashMap<String, String>>,
     State(state): State<AppState>,
-) -> Result<Json<SuccessResponse<crate::domain::IssTrend>>, ApiError> {
-    let service = IssService::new(state.pool.clone(), state.http_client.clone());
-    let trend = service.get_trend().await?;
-    Ok(Json(SuccessResponse::new(trend)))
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_trend(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_position(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<crate::domain::IssOrbitState>>, ApiError> {
+    sat_position(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_passes(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_passes(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_track(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_track(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_history(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_history(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_over(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    sat_over(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_stream_sse(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    sat_stream_sse(Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn iss_stream_ws(
+    ws: WebSocketUpgrade,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Response {
+    sat_stream_ws(ws, Path(ISS_NORAD_ID), Query(q), State(state)).await
+}
+
+pub async fn earth_terminator(
+    Query(q): Query<HashMap<String, String>>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let at = query_time(&q, "at")?.unwrap_or_else(Utc::now);
+    let (sub_lat, sub_lon) = astro::subsolar_point(at);
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "type": "FeatureCollection",
+        "features": [{
+            "type": "Feature",
+            "geometry": { "type": "Polygon", "coordinates": [astro::night_polygon(at)] },
+            "properties": { "kind": "night", "at": at }
+        }, {
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [sub_lon, sub_lat] },
+            "properties": { "kind": "subsolar", "at": at }
+        }]
+    }))))
+}
+
+pub async fn alerts(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let location_id = query_param::<i64>(&q, "location_id")?;
+    let since = query_time(&q, "since")?;
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(50);
+    if !(1..=1000).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 1000".to_string()));
+    }
+
+    let service = AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let items = service.list_alerts(location_id, since, limit).await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "items": items
+    }))))
+}
+
+pub async fn alert_locations(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let service = AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let items = service.list_locations().await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "items": items
+    }))))
+}
+
+pub async fn alert_location_create(
+    State(state): State<AppState>,
+    body: Result<Json<NewObserverLocation>, JsonRejection>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let Json(loc) = body.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let service = AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let location = service.add_location(loc).await?;
+    Ok(Json(SuccessResponse::new(serde_json::to_value(location).unwrap_or_default())))
+}
+
+pub async fn alert_location_delete(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let service = AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    service.delete_location(id).await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "deleted": id
+    }))))
 }
 
 pub async fn osdr_sync(
+    Query(q): Query<HashMap<String, String>>,
     State(state): State<AppState>,
 ) -> Result<Json<SuccessResponse<Value>>, ApiError> {
-    let service = OsdrService::new(state.pool.clone(), state.http_client.clone());
-    service.sync().await?;
+    let full = matches!(q.get("full").map(String::as_str), Some("1" | "true"));
+    let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    // та же блокировка, что у задачи osdr: обход не идёт параллельно с плановым
+    let lock_name = state.jobs.get("osdr").map(|h| h.lock_key()).unwrap_or_else(|| "osdr".to_string());
+    let stats = with_job_lock(&state, &lock_name, service.sync(full))
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::Conflict("osdr sync is already running".to_string()))??;
     Ok(Json(SuccessResponse::new(serde_json::json!({
-        "status": "ok"
+        "status": "ok",
+        "stats": stats
     }))))
 }
 
 pub async fn osdr_list(
+    Query(q): Query<HashMap<String, String>>,
     State(state): State<AppState>,
 ) -> Result<Json<SuccessResponse<Value>>, ApiError> {
-    let limit = std::env::var("OSDR_LIST_LIMIT")
-        .ok()
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(20);
-    
-    let service = OsdrService::new(state.pool.clone(), state.http_client.clone());
-    let items = service.list(limit).await?;
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(state.config.osdr_list_limit);
+    let offset = query_param::<i64>(&q, "offset")?.unwrap_or(0);
+    if !(1..=500).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 500".to_string()));
+    }
+    if offset < 0 {
+        return Err(ApiError::Validation("offset must not be negative".to_string()));
+    }
+
+    let sort = match q.get("sort").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(s) => OsdrSort::parse(s).ok_or_else(|| {
+            ApiError::Validation("sort must be one of updated_at/title/inserted_at".to_string())
+        })?,
+        None => OsdrSort::InsertedAt,
+    };
+    // по умолчанию: новые сверху, title - по алфавиту
+    let descending = match q.get("order").map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("asc") => false,
+        Some("desc") => true,
+        None | Some("") => sort != OsdrSort::Title,
+        Some(_) => return Err(ApiError::Validation("order must be asc or desc".to_string())),
+    };
+    let after = match q.get("cursor").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(_) if offset > 0 => {
+            return Err(ApiError::Validation("use either cursor or offset, not both".to_string()))
+        }
+        Some(c) => Some(decode_cursor(c, sort, descending)?),
+        None => None,
+    };
+
+    // по умолчанию пропавшие из OSDR датасеты не показываются
+    let removed = match q.get("state").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        None | Some("active") => Some(false),
+        Some("removed") => Some(true),
+        Some("all") => None,
+        Some(_) => return Err(ApiError::Validation("state must be one of active/removed/all".to_string())),
+    };
+
+    let query = OsdrListQuery {
+        status: q.get("status").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        updated_since: query_time(&q, "updated_since")?,
+        q: q.get("q").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        sort,
+        descending,
+        limit,
+        offset,
+        after,
+        removed,
+    };
+
+    let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let (items, total, next_cursor) = service.list(&query).await?;
     
     let out: Vec<Value> = items.into_iter().map(|item| {
         serde_json::json!({
@@ -80,15 +526,85 @@ pub async fn osdr_list(
             "status": item.status,
             "updated_at": item.updated_at,
             "inserted_at": item.inserted_at,
+            "removed_at": item.removed_at,
             "raw": item.raw,
         })
     }).collect();
     
     Ok(Json(SuccessResponse::new(serde_json::json!({
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+        "sort": sort.as_str(),
+        "order": if descending { "desc" } else { "asc" },
+        "next_cursor": next_cursor,
         "items": out
     }))))
 }
 
+pub async fn osdr_detail(
+    Path(dataset_id): Path<String>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let refresh = matches!(q.get("refresh").map(String::as_str), Some("1" | "true"));
+    let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let detail = service.detail(&dataset_id, refresh).await?;
+    Ok(Json(SuccessResponse::new(detail)))
+}
+
+pub async fn osdr_history(
+    Path(dataset_id): Path<String>,
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(20);
+    if !(1..=200).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 200".to_string()));
+    }
+    let with_raw = matches!(q.get("raw").map(String::as_str), Some("1" | "true"));
+
+    let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let history = service.history(&dataset_id, limit, with_raw).await?;
+    Ok(Json(SuccessResponse::new(history)))
+}
+
+pub async fn osdr_search(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let text = q
+        .get("q")
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| ApiError::Validation("q is required".to_string()))?;
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(20);
+    let offset = query_param::<i64>(&q, "offset")?.unwrap_or(0);
+    if !(1..=100).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 100".to_string()));
+    }
+    if offset < 0 {
+        return Err(ApiError::Validation("offset must not be negative".to_string()));
+    }
+    let any = match q.get("match").map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        None => None,
+        Some("all") => Some(false),
+        Some("any") => Some(true),
+        Some(_) => return Err(ApiError::Validation("match must be all or any".to_string())),
+    };
+
+    let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+    let (items, total, any) = service.search(text, any, limit, offset).await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "q": text,
+        "match": if any { "any" } else { "all" },
+        "total": total,
+        "limit": limit,
+        "offset": offset,
+        "items": items
+    }))))
+}
+
 pub async fn space_latest(
     Path(src): Path<String>,
     State(state): State<AppState>,
@@ -160,3 +676,145 @@ pub async fn space_summary(
     Ok(Json(SuccessResponse::new(summary)))
 }
 
+fn query_param<T: std::str::FromStr>(q: &HashMap<String, String>, key: &str) -> Result<Option<T>, ApiError> {
+    match q.get(key).map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(s) => s
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| ApiError::Validation(format!("invalid value for {}", key))),
+        None => Ok(None),
+    }
+}
+
+fn query_time(q: &HashMap<String, String>, key: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+    match q.get(key).map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(s) => DateTime::parse_from_rfc3339(s)
+            .map(|dt| Some(dt.with_timezone(&Utc)))
+            .map_err(|_| ApiError::Validation(format!("{} must be an RFC 3339 timestamp", key))),
+        None => Ok(None),
+    }
+}
+
+// "90s", "30m", "1h", "2d" или просто секунды
+fn parse_window(s: &str) -> Result<chrono::Duration, ApiError> {
+    let s = s.trim();
+    let (digits, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let n = digits
+        .parse::<i64>()
+        .map_err(|_| ApiError::Validation("invalid window".to_string()))?;
+    if n <= 0 {
+        return Err(ApiError::Validation("window must be positive".to_string()));
+    }
+    // try_*: огромное n - ошибка валидации, а не паника
+    let d = match unit {
+        "" | "s" => chrono::Duration::try_seconds(n),
+        "m" => chrono::Duration::try_minutes(n),
+        "h" => chrono::Duration::try_hours(n),
+        "d" => chrono::Duration::try_days(n),
+        _ => return Err(ApiError::Validation("invalid window unit, use s/m/h/d".to_string())),
+    };
+    d.ok_or_else(|| ApiError::Validation("window is out of range".to_string()))
+}
+
+// Изменяющие запросы /jobs - только с Authorization: Bearer <JOBS_ADMIN_TOKEN>;
+// без токена в конфигурации они отключены
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(expected) = state.config.jobs_admin_token.as_deref() else {
+        return Err(ApiError::Forbidden("job management is disabled: JOBS_ADMIN_TOKEN is not set".to_string()));
+    };
+    let given = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    // сравнение без раннего выхода: время ответа не выдаёт совпавший префикс
+    let same = given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0;
+    if same {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden("invalid or missing admin token".to_string()))
+    }
+}
+
+pub async fn job_runs(
+    Query(q): Query<HashMap<String, String>>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let limit = query_param::<i64>(&q, "limit")?.unwrap_or(50);
+    if !(1..=500).contains(&limit) {
+        return Err(ApiError::Validation("limit must be between 1 and 500".to_string()));
+    }
+    let job = q.get("job").map(String::as_str).filter(|s| !s.is_empty());
+    let outcome = q.get("outcome").map(String::as_str).filter(|s| !s.is_empty());
+    if let Some(o) = outcome {
+        if !matches!(o, "running" | "success" | "error" | "skipped") {
+            return Err(ApiError::Validation(format!(
+                "outcome must be running, success, error or skipped, got {}",
+                o
+            )));
+        }
+    }
+    let runs = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).runs(job, outcome, limit).await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({
+        "job": job,
+        "outcome": outcome,
+        "limit": limit,
+        "items": runs
+    }))))
+}
+
+pub async fn jobs_list(State(state): State<AppState>) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let jobs = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).list().await?;
+    Ok(Json(SuccessResponse::new(serde_json::json!({ "items": jobs }))))
+}
+
+pub async fn job_get(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    let job = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).get(&name).await?;
+    Ok(Json(SuccessResponse::new(job)))
+}
+
+pub async fn job_run(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    require_admin(&state, &headers)?;
+    let job = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).trigger(&name).await?;
+    Ok(Json(SuccessResponse::new(job)))
+}
+
+pub async fn job_pause(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    require_admin(&state, &headers)?;
+    let job = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).set_paused(&name, true).await?;
+    Ok(Json(SuccessResponse::new(job)))
+}
+
+pub async fn job_resume(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    require_admin(&state, &headers)?;
+    let job = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).set_paused(&name, false).await?;
+    Ok(Json(SuccessResponse::new(job)))
+}
+
+pub async fn job_update(
+    Path(name): Path<String>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<JobPatch>, JsonRejection>,
+) -> Result<Json<SuccessResponse<Value>>, ApiError> {
+    require_admin(&state, &headers)?;
+    let Json(patch) = body.map_err(|e| ApiError::Validation(e.body_text()))?;
+    let job = JobService::new(state.pool.clone(), state.jobs.clone(), state.leader.clone()).reschedule(&name, patch).await?;
+    Ok(Json(SuccessResponse::new(job)))
+}
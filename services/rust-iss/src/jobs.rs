@@ -0,0 +1,678 @@
+use crate::app_state::AppState;
+use crate::clients;
+use crate::config::{parse_cron, Config, CronExpr, JobTiming};
+use crate::domain::{JobSettings, RunStats, Satellite};
+use crate::error::ApiError;
+use crate::lock::DistributedLock;
+use crate::repo::{JobSettingsRepo, SatelliteRepo, SyncRunRepo};
+use crate::services::{AlertService, IssService, OsdrService, SpaceCacheService};
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use rand::Rng;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, info, warn};
+
+// Фоновая задача планировщика. Новый источник данных - это реализация Job
+// и одна строка в JobRegistry::load.
+pub trait Job: Send + Sync {
+    // имя в логах и sync_runs
+    fn name(&self) -> String;
+
+    fn schedule(&self) -> Schedule;
+
+    // запуск дольше считается ошибкой и прерывается
+    fn timeout(&self) -> Duration;
+
+    // задачи с одним ключом не выполняются одновременно (в т.ч. на разных репликах)
+    fn lock_key(&self) -> String {
+        self.name()
+    }
+
+    fn run<'a>(&'a self, state: &'a AppState) -> BoxFuture<'a, anyhow::Result<RunStats>>;
+}
+
+#[derive(Debug, Clone)]
+pub enum Trigger {
+    // пауза после окончания запуска
+    Every(Duration),
+    // ближайший слот cron-выражения (UTC)
+    Cron(Box<CronExpr>),
+}
+
+// предел интервала и джиттера, задаваемых через /jobs
+pub const MAX_SCHEDULE_SECS: i64 = 30 * 86400;
+
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    pub trigger: Trigger,
+    // к каждому ожиданию добавляется случайное 0..jitter
+    pub jitter: Duration,
+    pub initial_delay: Duration,
+}
+
+impl Schedule {
+    pub fn every_secs(secs: u64) -> Self {
+        Self {
+            trigger: Trigger::Every(Duration::from_secs(secs.max(1))),
+            jitter: Duration::ZERO,
+            initial_delay: Duration::ZERO,
+        }
+    }
+
+    // cron из конфигурации заменяет интервал
+    pub fn with_timing(mut self, timing: &JobTiming) -> Self {
+        if let Some(cron) = &timing.cron {
+            self.trigger = Trigger::Cron(Box::new(cron.clone()));
+        }
+        self.jitter = Duration::from_secs(timing.jitter_secs);
+        self.initial_delay = Duration::from_secs(timing.initial_delay_secs);
+        self
+    }
+
+    // Настройки из /jobs поверх конфигурации
+    pub fn with_settings(mut self, settings: &JobSettings) -> anyhow::Result<Self> {
+        if let Some(expr) = &settings.cron {
+            self.trigger = Trigger::Cron(Box::new(parse_cron(expr)?));
+        } else if let Some(secs) = settings.interval_seconds {
+            self.trigger = Trigger::Every(Duration::from_secs(secs.max(1) as u64));
+        }
+        if let Some(secs) = settings.jitter_seconds {
+            self.jitter = Duration::from_secs(secs.max(0) as u64);
+        }
+        Ok(self)
+    }
+
+    pub fn describe(&self) -> Value {
+        let mut v = match &self.trigger {
+            Trigger::Every(d) => serde_json::json!({ "interval_seconds": d.as_secs() }),
+            Trigger::Cron(cron) => serde_json::json!({ "cron": cron.expr }),
+        };
+        v["jitter_seconds"] = self.jitter.as_secs().into();
+        v["initial_delay_seconds"] = self.initial_delay.as_secs().into();
+        v
+    }
+
+    // Интервальная задача запускается сразу после initial_delay, cron - в ближайший слот после неё
+    pub fn first_delay(&self) -> Duration {
+        let base = match &self.trigger {
+            Trigger::Every(_) => self.initial_delay,
+            // until_next считает от текущего момента: initial_delay уже внутри
+            Trigger::Cron(cron) => {
+                let from = Utc::now() + chrono::Duration::from_std(self.initial_delay).unwrap_or_default();
+                until_next(&cron.schedule, from)
+            }
+        };
+        base + self.random_jitter()
+    }
+
+    pub fn next_delay(&self) -> Duration {
+        let base = match &self.trigger {
+            Trigger::Every(d) => *d,
+            Trigger::Cron(cron) => until_next(&cron.schedule, Utc::now()),
+        };
+        base + self.random_jitter()
+    }
+
+    fn random_jitter(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return Duration::ZERO;
+        }
+        let ms = rand::thread_rng().gen_range(0..=self.jitter.as_millis() as u64);
+        Duration::from_millis(ms)
+    }
+}
+
+// Время от текущего момента до первого слота после `from`; у выражения без будущих слотов - сутки
+fn until_next(cron: &cron::Schedule, from: DateTime<Utc>) -> Duration {
+    let now = Utc::now();
+    cron.after(&from)
+        .next()
+        .and_then(|at| (at - now).to_std().ok())
+        .unwrap_or(Duration::from_secs(86400))
+}
+
+// после прерывания по сроку остановки - на запись в sync_runs и снятие блокировок
+const ABORT_GRACE: Duration = Duration::from_secs(10);
+
+// Задача в планировщике: действующее расписание и состояние цикла.
+// Управляется через /jobs; изменения применяются без перезапуска.
+pub struct JobHandle {
+    job: Arc<dyn Job>,
+    name: String,
+    schedule: Mutex<Schedule>,
+    paused: AtomicBool,
+    running: AtomicBool,
+    run_now: AtomicBool,
+    next_run_at: Mutex<Option<DateTime<Utc>>>,
+    wake: Notify,
+}
+
+impl JobHandle {
+    fn new(job: Arc<dyn Job>) -> Self {
+        Self {
+            name: job.name(),
+            schedule: Mutex::new(job.schedule()),
+            job,
+            paused: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            run_now: AtomicBool::new(false),
+            next_run_at: Mutex::new(None),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        self.schedule.lock().unwrap().clone()
+    }
+
+    pub fn lock_key(&self) -> String {
+        self.job.lock_key()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.job.timeout()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn next_run_at(&self) -> Option<DateTime<Utc>> {
+        *self.next_run_at.lock().unwrap()
+    }
+
+    // Внеочередной запуск, в том числе на паузе; во время запуска - сразу после него
+    pub fn trigger(&self) {
+        self.run_now.store(true, Ordering::SeqCst);
+        self.wake.notify_one();
+    }
+
+    // Текущий запуск не прерывается; после возобновления отсчёт идёт заново
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        self.plan(self.schedule().next_delay());
+        self.wake.notify_one();
+    }
+
+    // Следующий запуск пересчитывается от текущего момента
+    pub fn reschedule(&self, schedule: Schedule) {
+        let delay = schedule.next_delay();
+        *self.schedule.lock().unwrap() = schedule;
+        self.plan(delay);
+        self.wake.notify_one();
+    }
+
+    // Настройки из job_settings (при старте и по уведомлению с другой реплики);
+    // расписание пересчитывается, только если что-то изменилось
+    fn apply(&self, settings: &JobSettings) {
+        let schedule = match self.schedule().with_settings(settings) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                error!("{} job: stored schedule ignored: {}", self.name, e);
+                self.schedule()
+            }
+        };
+        let changed = self.paused.swap(settings.paused, Ordering::SeqCst) != settings.paused
+            || schedule.describe() != self.schedule().describe();
+        if changed {
+            let delay = schedule.next_delay();
+            *self.schedule.lock().unwrap() = schedule;
+            self.plan(delay);
+            self.wake.notify_one();
+        }
+    }
+
+    // next_run_at - единственный источник времени следующего запуска; на паузе пусто.
+    // Непредставимая задержка - ошибка: задача не планируется, а не запускается сразу
+    fn plan(&self, delay: Duration) {
+        let at = if self.is_paused() {
+            None
+        } else {
+            let at = chrono::Duration::from_std(delay).ok().and_then(|d| Utc::now().checked_add_signed(d));
+            if at.is_none() {
+                error!("{} job: delay of {}s is out of range, not scheduled", self.name, delay.as_secs());
+            }
+            at
+        };
+        *self.next_run_at.lock().unwrap() = at;
+    }
+
+    // Цикл завершается по state.shutdown: ожидание обрывается сразу,
+    // текущий запуск доводится до конца (или прерывается через abort)
+    async fn run_loop(self: Arc<Self>, state: AppState, abort: CancellationToken) {
+        let delay = self.schedule().first_delay();
+        if !delay.is_zero() {
+            info!("{} job: first run in {}s", self.name, delay.as_secs());
+        }
+        self.plan(delay);
+        while !state.shutdown.is_cancelled() {
+            // пробуждение без срабатывания таймера - пауза, возобновление или новое расписание
+            let fired = match self.next_run_at() {
+                Some(at) => {
+                    let wait = (at - Utc::now()).to_std().unwrap_or_default();
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => true,
+                        _ = self.wake.notified() => false,
+                        _ = state.shutdown.cancelled() => break,
+                    }
+                }
+                None => {
+                    tokio::select! {
+                        _ = self.wake.notified() => false,
+                        _ = state.shutdown.cancelled() => break,
+                    }
+                }
+            };
+
+            let manual = self.run_now.swap(false, Ordering::SeqCst);
+            // по расписанию - только на ведущей реплике; ручной запуск - на любой
+            if fired && !manual && !state.leader.is_leader() {
+                debug!("{} job: not the leader, skipping scheduled run", self.name);
+                self.plan(self.schedule().next_delay());
+                continue;
+            }
+
+            if fired || manual {
+                *self.next_run_at.lock().unwrap() = None;
+                self.running.store(true, Ordering::SeqCst);
+                if let Err(e) = run_job(&state, self.job.as_ref(), &abort).await {
+                    error!("{} job error: {:?}", self.name, e);
+                }
+                self.running.store(false, Ordering::SeqCst);
+                self.plan(self.schedule().next_delay());
+            }
+        }
+        *self.next_run_at.lock().unwrap() = None;
+    }
+}
+
+pub struct JobRegistry {
+    jobs: Vec<Arc<JobHandle>>,
+    tracker: TaskTracker,
+    // прерывание запусков, не успевших завершиться при остановке
+    abort: CancellationToken,
+}
+
+impl JobRegistry {
+    // Спутники берутся из реестра на момент старта; поверх конфигурации -
+    // настройки, сохранённые через /jobs
+    pub async fn load(pool: &PgPool, config: &Config) -> anyhow::Result<Self> {
+        let intervals = &config.fetch_intervals;
+        let schedule = |secs: u64, key: &str| Schedule::every_secs(secs).with_timing(&config.job_timing(key));
+
+        let mut jobs: Vec<Arc<dyn Job>> = vec![Arc::new(OsdrJob { schedule: schedule(intervals.osdr, "osdr") })];
+
+        // у спутников свои интервалы; cron/jitter общие (ISS_*, TLE_*)
+        for sat in SatelliteRepo::list_enabled(pool).await? {
+            // TLE раньше позиции: без position_url позиция считается по TLE
+            if sat.tle_url.is_some() {
+                jobs.push(Arc::new(SatTleJob {
+                    norad_id: sat.norad_id,
+                    schedule: schedule(sat.tle_every_seconds.max(1) as u64, "tle"),
+                }));
+            }
+            jobs.push(Arc::new(SatPositionJob {
+                norad_id: sat.norad_id,
+                schedule: schedule(sat.fetch_every_seconds.max(1) as u64, "iss"),
+            }));
+        }
+
+        for (source, secs, key) in [
+            (SpaceSource::Apod, intervals.apod, "apod"),
+            (SpaceSource::Neo, intervals.neo, "neo"),
+            (SpaceSource::Donki, intervals.donki, "donki"),
+            (SpaceSource::Spacex, intervals.spacex, "spacex"),
+        ] {
+            jobs.push(Arc::new(SpaceCacheJob { source, schedule: schedule(secs, key) }));
+        }
+
+        let registry = Self {
+            jobs: jobs.into_iter().map(|job| Arc::new(JobHandle::new(job))).collect(),
+            tracker: TaskTracker::new(),
+            abort: CancellationToken::new(),
+        };
+        registry.reload(pool).await?;
+        Ok(registry)
+    }
+
+    // Пауза и расписание меняются через /jobs на любой реплике; каждая
+    // перечитывает их отсюда
+    pub async fn reload(&self, pool: &PgPool) -> Result<(), ApiError> {
+        for settings in JobSettingsRepo::list(pool).await? {
+            if let Some(handle) = self.get(&settings.name) {
+                handle.apply(&settings);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn jobs(&self) -> &[Arc<JobHandle>] {
+        &self.jobs
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<JobHandle>> {
+        self.jobs.iter().find(|h| h.name == name).cloned()
+    }
+
+    // Каждая задача - свой цикл: ожидание по расписанию, запуск
+    pub fn spawn_all(&self, state: &AppState) {
+        for handle in &self.jobs {
+            self.tracker.spawn(handle.clone().run_loop(state.clone(), self.abort.clone()));
+        }
+    }
+
+    // Ожидание циклов после отмены state.shutdown. К сроку незавершённые запуски
+    // прерываются: в sync_runs пишется ошибка, блокировки снимаются.
+    pub async fn drain(&self, deadline: tokio::time::Instant) {
+        self.tracker.close();
+        if tokio::time::timeout_at(deadline, self.tracker.wait()).await.is_ok() {
+            return;
+        }
+        let running: Vec<&str> = self.jobs.iter().filter(|h| h.is_running()).map(|h| h.name()).collect();
+        warn!("shutdown deadline reached, interrupting jobs: {}", running.join(", "));
+        self.abort.cancel();
+        if tokio::time::timeout(ABORT_GRACE, self.tracker.wait()).await.is_err() {
+            error!("jobs did not stop within {}s after interrupt", ABORT_GRACE.as_secs());
+        }
+    }
+}
+
+pub async fn run_job(state: &AppState, job: &dyn Job, abort: &CancellationToken) -> anyhow::Result<()> {
+    let name = job.name();
+    let timeout = job.timeout();
+    run_with_lock(state, &job.lock_key(), &name, || async {
+        // при прерывании future запуска сбрасывается: незакоммиченные транзакции откатываются
+        tokio::select! {
+            res = tokio::time::timeout(timeout, job.run(state)) => {
+                res.map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))?
+            }
+            _ = abort.cancelled() => Err(anyhow::anyhow!("interrupted by shutdown")),
+        }
+    })
+    .await
+}
+
+// Ручной запуск из HTTP-запроса под блокировкой задачи lock_name: не пересекается
+// с запуском по расписанию ни на одной реплике. None - задача уже выполняется.
+pub async fn with_job_lock<F: Future>(
+    state: &AppState,
+    lock_name: &str,
+    fut: F,
+) -> anyhow::Result<Option<F::Output>> {
+    let lock = DistributedLock::new(state.pool.clone(), &state.config)?;
+    let Some(mut guard) = lock.try_acquire(lock_name).await? else {
+        return Ok(None);
+    };
+    let result = guard.hold(fut).await;
+    guard.release().await;
+    result.map(Some)
+}
+
+async fn run_with_lock<F, Fut>(
+    state: &AppState,
+    lock_name: &str,
+    job: &str,
+    task: F,
+) -> anyhow::Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<RunStats>>,
+{
+    let lock = DistributedLock::new(state.pool.clone(), &state.config)?;
+    match lock.try_acquire(lock_name).await? {
+        Some(mut guard) => {
+            let result = guard.hold(record_run(state, job, task)).await;
+            guard.release().await;
+            result?
+        }
+        None => {
+            info!("Skipping {} task - already running", job);
+            if let Err(e) = SyncRunRepo::skipped(&state.pool, job).await {
+                error!("sync_runs write error for {}: {}", job, e);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Запуск задачи с записью в sync_runs; сбой журнала не мешает самой задаче
+async fn record_run<F, Fut>(state: &AppState, job: &str, task: F) -> anyhow::Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<RunStats>>,
+{
+    let run_id = match SyncRunRepo::start(&state.pool, job).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            error!("sync_runs write error for {}: {}", job, e);
+            None
+        }
+    };
+
+    let (result, upstream_ms) = clients::measure_upstream(task()).await;
+
+    if let Some(id) = run_id {
+        let upstream_ms = (upstream_ms > 0).then_some(upstream_ms as i64);
+        let finished = match &result {
+            Ok(stats) => SyncRunRepo::finish(&state.pool, id, "success", None, stats, upstream_ms).await,
+            Err(e) => {
+                let text = format!("{:#}", e);
+                SyncRunRepo::finish(&state.pool, id, "error", Some(&text), &Default::default(), upstream_ms).await
+            }
+        };
+        if let Err(e) = finished {
+            error!("sync_runs write error for {}: {}", job, e);
+        }
+        if let Err(e) = SyncRunRepo::prune(&state.pool, job, state.config.sync_runs_keep_days).await {
+            error!("sync_runs prune error for {}: {}", job, e);
+        }
+    }
+    result.map(|_| ())
+}
+
+struct OsdrJob {
+    schedule: Schedule,
+}
+
+impl Job for OsdrJob {
+    fn name(&self) -> String {
+        "osdr".to_string()
+    }
+
+    fn schedule(&self) -> Schedule {
+        self.schedule.clone()
+    }
+
+    // полный обход - сотни страниц
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(1800)
+    }
+
+    fn run<'a>(&'a self, state: &'a AppState) -> BoxFuture<'a, anyhow::Result<RunStats>> {
+        Box::pin(async move {
+            let service = OsdrService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+            let stats = service.sync(false).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+            Ok(RunStats::from(&stats))
+        })
+    }
+}
+
+// Свежая запись реестра: url и enabled могли поменяться после старта
+async fn satellite(state: &AppState, norad_id: i32) -> anyhow::Result<Option<Satellite>> {
+    let sat = SatelliteRepo::get(&state.pool, norad_id)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(sat.filter(|s| s.enabled))
+}
+
+fn satellite_service(state: &AppState, norad_id: i32) -> IssService {
+    IssService::for_satellite(state.pool.clone(), state.http_client.clone(), norad_id)
+        .with_regions(state.regions.clone())
+}
+
+struct SatPositionJob {
+    norad_id: i32,
+    schedule: Schedule,
+}
+
+impl Job for SatPositionJob {
+    fn name(&self) -> String {
+        format!("sat_{}", self.norad_id)
+    }
+
+    fn schedule(&self) -> Schedule {
+        self.schedule.clone()
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn run<'a>(&'a self, state: &'a AppState) -> BoxFuture<'a, anyhow::Result<RunStats>> {
+        Box::pin(async move {
+            let Some(sat) = satellite(state, self.norad_id).await? else {
+                return Ok(RunStats::default());
+            };
+            let fresh = satellite_service(state, self.norad_id)
+                .fetch_and_store(&sat)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            let alerts = AlertService::new(state.pool.clone(), state.http_client.clone(), &state.config);
+            alerts.check(self.norad_id).await.map_err(|e| anyhow::anyhow!("{}", e))?;
+            Ok(RunStats::single(fresh))
+        })
+    }
+}
+
+struct SatTleJob {
+    norad_id: i32,
+    schedule: Schedule,
+}
+
+impl Job for SatTleJob {
+    fn name(&self) -> String {
+        format!("sat_tle_{}", self.norad_id)
+    }
+
+    fn schedule(&self) -> Schedule {
+        self.schedule.clone()
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(120)
+    }
+
+    fn run<'a>(&'a self, state: &'a AppState) -> BoxFuture<'a, anyhow::Result<RunStats>> {
+        Box::pin(async move {
+            let Some(sat) = satellite(state, self.norad_id).await? else {
+                return Ok(RunStats::default());
+            };
+            let inserted = satellite_service(state, self.norad_id)
+                .fetch_tle_and_store(&sat)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            Ok(RunStats::single(inserted))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SpaceSource {
+    Apod,
+    Neo,
+    Donki,
+    Spacex,
+}
+
+struct SpaceCacheJob {
+    source: SpaceSource,
+    schedule: Schedule,
+}
+
+impl Job for SpaceCacheJob {
+    fn name(&self) -> String {
+        match self.source {
+            SpaceSource::Apod => "apod",
+            SpaceSource::Neo => "neo",
+            SpaceSource::Donki => "donki",
+            SpaceSource::Spacex => "spacex",
+        }
+        .to_string()
+    }
+
+    fn schedule(&self) -> Schedule {
+        self.schedule.clone()
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn run<'a>(&'a self, state: &'a AppState) -> BoxFuture<'a, anyhow::Result<RunStats>> {
+        Box::pin(async move {
+            let service = SpaceCacheService::new(state.pool.clone(), state.http_client.clone());
+            let err = |e: ApiError| anyhow::anyhow!("{}", e);
+            let changed = match self.source {
+                SpaceSource::Apod => service.fetch_apod().await.map_err(err)?,
+                SpaceSource::Neo => service.fetch_neo().await.map_err(err)?,
+                SpaceSource::Spacex => service.fetch_spacex().await.map_err(err)?,
+                // FLR и CME - две записи
+                SpaceSource::Donki => {
+                    let flr = service.fetch_donki_flr().await.map_err(err)?;
+                    let cme = service.fetch_donki_cme().await.map_err(err)?;
+                    return Ok(RunStats::single(flr).add(RunStats::single(cme)));
+                }
+            };
+            Ok(RunStats::single(changed))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(cron: &str) -> JobSettings {
+        JobSettings {
+            name: "test".to_string(),
+            paused: false,
+            interval_seconds: None,
+            cron: Some(cron.to_string()),
+            jitter_seconds: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    // GET /jobs отдаёт выражение пользователя: отправленное обратно в PATCH, оно
+    // даёт то же расписание
+    #[test]
+    fn describe_round_trips_cron() {
+        let schedule = Schedule::every_secs(60).with_settings(&settings("10 5 * * 1")).unwrap();
+        let shown = schedule.describe()["cron"].as_str().unwrap().to_string();
+        assert_eq!(shown, "10 5 * * 1");
+
+        let again = Schedule::every_secs(60).with_settings(&settings(&shown)).unwrap();
+        let from = Utc::now();
+        let slots = |s: &Schedule| match &s.trigger {
+            Trigger::Cron(c) => c.schedule.after(&from).take(5).collect::<Vec<_>>(),
+            Trigger::Every(_) => panic!("expected cron"),
+        };
+        assert_eq!(slots(&schedule), slots(&again));
+    }
+}
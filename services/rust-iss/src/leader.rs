@@ -0,0 +1,129 @@
+use crate::config::Config;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{PgPool, Row};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+// Выбор ведущей реплики: аренда "leader" в scheduler_leases с продлением.
+// Задачи по расписанию запускает только ведущая, HTTP обслуживают все.
+// Ведущая, упавшая без release(), теряет роль через ttl.
+
+const LEADER_LEASE: &str = "leader";
+
+#[derive(Debug, Serialize)]
+pub struct LeaderStatus {
+    pub instance_id: String,
+    pub is_leader: bool,
+    // текущая ведущая по данным БД (в т.ч. другая реплика)
+    pub leader: Option<String>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+}
+
+pub struct LeaderElection {
+    pool: PgPool,
+    holder: String,
+    token: uuid::Uuid,
+    ttl: Duration,
+    // до какого момента аренда гарантированно наша; None - не ведущая
+    valid_until: Mutex<Option<Instant>>,
+}
+
+impl LeaderElection {
+    pub fn new(pool: PgPool, config: &Config) -> Self {
+        Self {
+            pool,
+            holder: config.instance_id.clone(),
+            token: uuid::Uuid::new_v4(),
+            ttl: Duration::from_secs(config.leader_lease_seconds.max(3)),
+            valid_until: Mutex::new(None),
+        }
+    }
+
+    // Без успешного продления роль снимается заранее, до истечения аренды в БД
+    pub fn is_leader(&self) -> bool {
+        self.valid_until.lock().unwrap().is_some_and(|until| Instant::now() < until)
+    }
+
+    // Захват свободной/истёкшей аренды или продление своей
+    pub async fn try_acquire(&self) -> anyhow::Result<bool> {
+        let sent_at = Instant::now();
+        let row = sqlx::query(
+            "INSERT INTO scheduler_leases(name, holder, token, acquired_at, expires_at)
+             VALUES($1, $2, $3, now(), now() + $4::BIGINT * INTERVAL '1 second')
+             ON CONFLICT (name) DO UPDATE
+             SET holder = EXCLUDED.holder, token = EXCLUDED.token, expires_at = EXCLUDED.expires_at,
+                 acquired_at = CASE WHEN scheduler_leases.token = EXCLUDED.token
+                                    THEN scheduler_leases.acquired_at ELSE EXCLUDED.acquired_at END
+             WHERE scheduler_leases.expires_at < now() OR scheduler_leases.token = EXCLUDED.token
+             RETURNING token"
+        )
+        .bind(LEADER_LEASE)
+        .bind(&self.holder)
+        .bind(self.token)
+        .bind(self.ttl.as_secs() as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let acquired = row.is_some();
+        let was_leader = self.is_leader();
+        // отсчёт от отправки запроса: аренда в БД истекает не раньше
+        *self.valid_until.lock().unwrap() = acquired.then_some(sent_at + self.ttl);
+        if acquired && !was_leader {
+            info!("leader election: {} is now the leader", self.holder);
+        } else if !acquired && was_leader {
+            warn!("leader election: {} lost leadership", self.holder);
+        }
+        Ok(acquired)
+    }
+
+    // Попытки каждые ttl/3 до остановки сервиса
+    pub async fn run(&self, shutdown: CancellationToken) {
+        let every = self.ttl / 3;
+        loop {
+            // при ошибке роль сохраняется, пока не истёк срок последнего продления
+            if let Err(e) = self.try_acquire().await {
+                error!("leader election error: {}", e);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(every) => {}
+                _ = shutdown.cancelled() => break,
+            }
+        }
+    }
+
+    // При остановке: другая реплика перенимает роль сразу, не дожидаясь ttl
+    pub async fn release(&self) {
+        let was_leader = self.valid_until.lock().unwrap().take().is_some();
+        if !was_leader {
+            return;
+        }
+        match sqlx::query("DELETE FROM scheduler_leases WHERE name = $1 AND token = $2")
+            .bind(LEADER_LEASE)
+            .bind(self.token)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(_) => info!("leader election: {} released leadership", self.holder),
+            Err(e) => warn!("leader lease release error: {}", e),
+        }
+    }
+
+    pub async fn status(&self) -> anyhow::Result<LeaderStatus> {
+        let row = sqlx::query(
+            "SELECT holder, expires_at FROM scheduler_leases WHERE name = $1 AND expires_at >= now()"
+        )
+        .bind(LEADER_LEASE)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(LeaderStatus {
+            instance_id: self.holder.clone(),
+            is_leader: self.is_leader(),
+            leader: row.as_ref().map(|r| r.get("holder")),
+            lease_expires_at: row.as_ref().map(|r| r.get("expires_at")),
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use crate::config::Config;
+use sqlx::postgres::{PgConnectOptions, PgConnection};
+use sqlx::{ConnectOptions, Connection, PgPool};
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+// Распределённая блокировка задач между репликами.
+//
+// session - pg_try_advisory_lock на отдельном соединении, которое живёт всю
+// задачу: блокировка и разблокировка идут в одной сессии, а при падении
+// процесса сессия закрывается и блокировка снимается сама.
+// lease - строка в scheduler_leases с продлением (heartbeat); подходит, когда
+// сессию не удержать (pgbouncer в transaction-режиме). Брошенная аренда
+// освобождается через ttl.
+//
+// Кто что держит - представление scheduler_locks.
+
+// проверка соединения в session-режиме
+const SESSION_KEEPALIVE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Session,
+    Lease,
+}
+
+impl LockMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "session" => Some(LockMode::Session),
+            "lease" => Some(LockMode::Lease),
+            _ => None,
+        }
+    }
+}
+
+// FNV-1a 64: стабилен между сборками и версиями Rust, в отличие от DefaultHasher
+pub fn lock_key(name: &str) -> i64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in "rust_iss:".bytes().chain(name.bytes()) {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash as i64
+}
+
+pub struct DistributedLock {
+    pool: PgPool,
+    connect: PgConnectOptions,
+    mode: LockMode,
+    holder: String,
+    lease_ttl: Duration,
+}
+
+impl DistributedLock {
+    pub fn new(pool: PgPool, config: &Config) -> anyhow::Result<Self> {
+        let connect = config
+            .database_url
+            .parse::<PgConnectOptions>()?
+            .application_name(&format!("rust_iss {} lock", config.instance_id))
+            .disable_statement_logging();
+        Ok(Self {
+            pool,
+            connect,
+            mode: config.lock_mode,
+            holder: config.instance_id.clone(),
+            lease_ttl: Duration::from_secs(config.lock_lease_seconds.max(3)),
+        })
+    }
+
+    // None - блокировку держит кто-то другой
+    pub async fn try_acquire(&self, name: &str) -> anyhow::Result<Option<LockGuard>> {
+        match self.mode {
+            LockMode::Session => self.try_acquire_session(name).await,
+            LockMode::Lease => self.try_acquire_lease(name).await,
+        }
+    }
+
+    async fn try_acquire_session(&self, name: &str) -> anyhow::Result<Option<LockGuard>> {
+        let key = lock_key(name);
+        let mut conn = self.connect.connect().await?;
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut conn)
+            .await?;
+        if !acquired {
+            let _ = conn.close().await;
+            return Ok(None);
+        }
+
+        // ключ закрепляется за первым именем; другое имя с тем же ключом - коллизия
+        sqlx::query("INSERT INTO scheduler_lock_keys(key, name) VALUES($1, $2) ON CONFLICT (key) DO NOTHING")
+            .bind(key)
+            .bind(name)
+            .execute(&mut conn)
+            .await?;
+        let owner: String = sqlx::query_scalar("SELECT name FROM scheduler_lock_keys WHERE key = $1")
+            .bind(key)
+            .fetch_one(&mut conn)
+            .await?;
+        if owner != name {
+            let _ = conn.close().await;
+            anyhow::bail!("advisory lock key {} for {} collides with lock {}", key, name, owner);
+        }
+
+        Ok(Some(LockGuard {
+            name: name.to_string(),
+            inner: GuardInner::Session { conn: Some(Box::new(conn)), key },
+        }))
+    }
+
+    async fn try_acquire_lease(&self, name: &str) -> anyhow::Result<Option<LockGuard>> {
+        let token = uuid::Uuid::new_v4();
+        let row: Option<uuid::Uuid> = sqlx::query_scalar(
+            "INSERT INTO scheduler_leases(name, holder, token, acquired_at, expires_at)
+             VALUES($1, $2, $3, now(), now() + $4::BIGINT * INTERVAL '1 second')
+             ON CONFLICT (name) DO UPDATE
+             SET holder = EXCLUDED.holder, token = EXCLUDED.token,
+                 acquired_at = EXCLUDED.acquired_at, expires_at = EXCLUDED.expires_at
+             WHERE scheduler_leases.expires_at < now()
+             RETURNING token"
+        )
+        .bind(name)
+        .bind(&self.holder)
+        .bind(token)
+        .bind(self.lease_ttl.as_secs() as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|token| LockGuard {
+            name: name.to_string(),
+            inner: GuardInner::Lease {
+                pool: self.pool.clone(),
+                token,
+                ttl: self.lease_ttl,
+            },
+        }))
+    }
+}
+
+enum GuardInner {
+    Session { conn: Option<Box<PgConnection>>, key: i64 },
+    Lease { pool: PgPool, token: uuid::Uuid, ttl: Duration },
+}
+
+// Удерживаемая блокировка. Без release() соединение закрывается при drop
+// (session) или аренда истекает сама (lease).
+pub struct LockGuard {
+    name: String,
+    inner: GuardInner,
+}
+
+impl LockGuard {
+    // Выполняет future, продлевая блокировку; потеря блокировки прерывает её с ошибкой
+    pub async fn hold<F: Future>(&mut self, fut: F) -> anyhow::Result<F::Output> {
+        tokio::pin!(fut);
+        let every = match &self.inner {
+            GuardInner::Session { .. } => SESSION_KEEPALIVE,
+            GuardInner::Lease { ttl, .. } => *ttl / 3,
+        };
+        let mut tick = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+        loop {
+            tokio::select! {
+                out = &mut fut => return Ok(out),
+                _ = tick.tick() => self.heartbeat().await?,
+            }
+        }
+    }
+
+    async fn heartbeat(&mut self) -> anyhow::Result<()> {
+        match &mut self.inner {
+            GuardInner::Session { conn: Some(conn), .. } => {
+                conn.ping()
+                    .await
+                    .map_err(|e| anyhow::anyhow!("lock {} connection lost: {}", self.name, e))?;
+            }
+            GuardInner::Session { conn: None, .. } => anyhow::bail!("lock {} already released", self.name),
+            GuardInner::Lease { pool, token, ttl } => {
+                let res = sqlx::query(
+                    "UPDATE scheduler_leases SET expires_at = now() + $3::BIGINT * INTERVAL '1 second'
+                     WHERE name = $1 AND token = $2"
+                )
+                .bind(&self.name)
+                .bind(*token)
+                .bind(ttl.as_secs() as i64)
+                .execute(&*pool)
+                .await?;
+                if res.rows_affected() == 0 {
+                    anyhow::bail!("lease {} lost", self.name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn release(mut self) {
+        match &mut self.inner {
+            GuardInner::Session { conn, key } => {
+                if let Some(mut conn) = conn.take() {
+                    let released: Result<bool, _> = sqlx::query_scalar("SELECT pg_advisory_unlock($1)")
+                        .bind(*key)
+                        .fetch_one(&mut *conn)
+                        .await;
+                    if !matches!(released, Ok(true)) {
+                        warn!("lock {} was not held at release: {:?}", self.name, released);
+                    }
+                    let _ = conn.close().await;
+                }
+            }
+            GuardInner::Lease { pool, token, .. } => {
+                if let Err(e) = sqlx::query("DELETE FROM scheduler_leases WHERE name = $1 AND token = $2")
+                    .bind(&self.name)
+                    .bind(*token)
+                    .execute(&*pool)
+                    .await
+                {
+                    warn!("lease {} release error: {}", self.name, e);
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,27 @@
 mod app_state;
+mod astro;
 mod clients;
 mod config;
 mod domain;
 mod error;
+mod events;
+mod geo;
 mod handlers;
+mod jobs;
+mod leader;
+mod lock;
 mod repo;
 mod routes;
 mod services;
+mod sgp4;
 
 use app_state::AppState;
 use config::Config;
 use routes::create_router;
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use std::time::Duration;
-use tracing::{error, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 async fn init_db(pool: &sqlx::PgPool) -> anyhow::Result<()> {
@@ -29,6 +37,122 @@ async fn init_db(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     .execute(pool)
     .await?;
 
+    // реестр отслеживаемых спутников
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS satellites(
+            norad_id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            position_url TEXT,
+            tle_url TEXT,
+            fetch_every_seconds INTEGER NOT NULL DEFAULT 120,
+            tle_every_seconds INTEGER NOT NULL DEFAULT 21600,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("ALTER TABLE iss_fetch_log ADD COLUMN IF NOT EXISTS norad_id INTEGER NOT NULL DEFAULT 25544")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_iss_fetch_log_norad ON iss_fetch_log(norad_id, fetched_at)")
+        .execute(pool)
+        .await?;
+
+    // типизированные поля позиции; payload остаётся для аудита
+    sqlx::query(
+        "ALTER TABLE iss_fetch_log
+            ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS altitude_km DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS velocity_kmh DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS visibility TEXT,
+            ADD COLUMN IF NOT EXISTS footprint_km DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS solar_lat DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS solar_lon DOUBLE PRECISION,
+            ADD COLUMN IF NOT EXISTS observed_at TIMESTAMPTZ"
+    )
+    .execute(pool)
+    .await?;
+
+    // старые строки, payload которых не разобрался: повторно не сканируются
+    sqlx::query("ALTER TABLE iss_fetch_log ADD COLUMN IF NOT EXISTS parse_error TEXT")
+        .execute(pool)
+        .await?;
+
+    // страна/акватория под подспутниковой точкой (geo::RegionIndex)
+    sqlx::query(
+        "ALTER TABLE iss_fetch_log
+            ADD COLUMN IF NOT EXISTS region TEXT,
+            ADD COLUMN IF NOT EXISTS region_kind TEXT"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_tle_log(
+            id BIGSERIAL PRIMARY KEY,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            source_url TEXT NOT NULL,
+            norad_id INTEGER NOT NULL,
+            name TEXT,
+            line1 TEXT NOT NULL,
+            line2 TEXT NOT NULL,
+            epoch TIMESTAMPTZ NOT NULL,
+            UNIQUE (line1, line2)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_iss_tle_log_epoch ON iss_tle_log(norad_id, epoch)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_iss_fetch_log_fetched_at ON iss_fetch_log(fetched_at)")
+        .execute(pool)
+        .await?;
+
+    // точки наблюдения и уведомления о пролётах
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS observer_locations(
+            id BIGSERIAL PRIMARY KEY,
+            name TEXT NOT NULL,
+            latitude DOUBLE PRECISION NOT NULL,
+            longitude DOUBLE PRECISION NOT NULL,
+            radius_km DOUBLE PRECISION NOT NULL,
+            norad_id INTEGER NOT NULL DEFAULT 25544,
+            webhook_url TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS proximity_alerts(
+            id BIGSERIAL PRIMARY KEY,
+            location_id BIGINT NOT NULL REFERENCES observer_locations(id) ON DELETE CASCADE,
+            norad_id INTEGER NOT NULL,
+            log_id BIGINT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            latitude DOUBLE PRECISION NOT NULL,
+            longitude DOUBLE PRECISION NOT NULL,
+            distance_km DOUBLE PRECISION NOT NULL,
+            delivered_at TIMESTAMPTZ,
+            delivery_error TEXT,
+            UNIQUE (location_id, log_id)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_proximity_alerts_created ON proximity_alerts(created_at DESC)")
+        .execute(pool)
+        .await?;
+
     // OSDR
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS osdr_items(
@@ -51,6 +175,241 @@ async fn init_db(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     .execute(pool)
     .await?;
 
+    // датасеты, пропавшие из каталога при полном обходе
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS removed_at TIMESTAMPTZ")
+        .execute(pool)
+        .await?;
+
+    // карточки датасетов (файлы, assays, образцы, факторы), загружаются по первому запросу
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_item_details(
+            dataset_id TEXT PRIMARY KEY,
+            source_url TEXT NOT NULL,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            raw_hash TEXT,
+            files JSONB,
+            assays JSONB,
+            samples JSONB,
+            factors JSONB,
+            document JSONB NOT NULL
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // неудачные загрузки карточек: повтор не раньше retry_at (экспоненциально)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_detail_failures(
+            dataset_id TEXT PRIMARY KEY,
+            raw_hash TEXT,
+            error TEXT NOT NULL,
+            failures INTEGER NOT NULL DEFAULT 1,
+            failed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            retry_at TIMESTAMPTZ NOT NULL
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // история изменений: хэш текущего raw и редакции по датасетам
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS raw_hash TEXT")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("UPDATE osdr_items SET raw_hash = md5(raw::TEXT) WHERE raw_hash IS NULL")
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_item_versions(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            raw_hash TEXT NOT NULL,
+            title TEXT,
+            status TEXT,
+            updated_at TIMESTAMPTZ,
+            raw JSONB NOT NULL,
+            recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            UNIQUE (dataset_id, version)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // строки, записанные до появления истории, становятся версией 1
+    sqlx::query(
+        "INSERT INTO osdr_item_versions(dataset_id, version, raw_hash, title, status, updated_at, raw, recorded_at)
+         SELECT dataset_id, 1, raw_hash, title, status, updated_at, raw, inserted_at
+         FROM osdr_items i
+         WHERE dataset_id IS NOT NULL
+           AND NOT EXISTS (SELECT 1 FROM osdr_item_versions v WHERE v.dataset_id = i.dataset_id)"
+    )
+    .execute(pool)
+    .await?;
+
+    // полнотекстовый поиск: текстовые поля raw встречаются и плоско, и в "metadata"
+    let search_ddl = [
+        "CREATE OR REPLACE FUNCTION osdr_description(raw JSONB) RETURNS TEXT
+         LANGUAGE SQL IMMUTABLE AS $$
+             SELECT coalesce(raw->>'description', raw->>'study description',
+                             raw#>>'{metadata,study description}', raw->>'summary', '')
+         $$",
+        "CREATE OR REPLACE FUNCTION osdr_facets(raw JSONB) RETURNS TEXT
+         LANGUAGE SQL IMMUTABLE AS $$
+             SELECT coalesce(raw->>'organism', raw#>>'{metadata,organism}', '') || ' ' ||
+                    coalesce(raw->>'assay_type', raw->>'study assay technology type',
+                             raw#>>'{metadata,study assay technology type}', '') || ' ' ||
+                    coalesce(raw->>'factors', raw->>'study factor name',
+                             raw#>>'{metadata,study factor name}', '')
+         $$",
+        "ALTER TABLE osdr_items ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+             setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+             setweight(to_tsvector('english', osdr_description(raw)), 'B') ||
+             setweight(to_tsvector('english', osdr_facets(raw)), 'C')
+         ) STORED",
+    ];
+    // STORED-колонка не пересчитывается при CREATE OR REPLACE функций: в комментарии
+    // колонки - md5 определений, при расхождении колонка (и индекс) создаются заново.
+    // Под advisory-блокировкой, чтобы реплики не пересоздавали её одновременно
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('rust_iss:osdr_search'))")
+        .execute(&mut *tx)
+        .await?;
+    let wanted: String = sqlx::query_scalar("SELECT md5($1)")
+        .bind(search_ddl.concat())
+        .fetch_one(&mut *tx)
+        .await?;
+    let current: Option<String> = sqlx::query_scalar(
+        "SELECT col_description(attrelid, attnum) FROM pg_attribute
+         WHERE attrelid = 'osdr_items'::regclass AND attname = 'search' AND NOT attisdropped"
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .flatten();
+    if current.as_deref() != Some(wanted.as_str()) {
+        info!("rebuilding osdr_items.search");
+        sqlx::query("ALTER TABLE osdr_items DROP COLUMN IF EXISTS search")
+            .execute(&mut *tx)
+            .await?;
+        for ddl in search_ddl {
+            sqlx::query(ddl).execute(&mut *tx).await?;
+        }
+        // md5 - только hex, подстановка в текст безопасна
+        sqlx::query(&format!("COMMENT ON COLUMN osdr_items.search IS '{}'", wanted))
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_osdr_items_search ON osdr_items USING GIN (search)")
+        .execute(pool)
+        .await?;
+
+    // курсоры инкрементальной синхронизации внешних источников
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sync_state(
+            source TEXT PRIMARY KEY,
+            high_water TIMESTAMPTZ,
+            last_full_at TIMESTAMPTZ,
+            last_success_at TIMESTAMPTZ
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // журнал запусков фоновых задач (см. /jobs/runs)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sync_runs(
+            id BIGSERIAL PRIMARY KEY,
+            job TEXT NOT NULL,
+            started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            finished_at TIMESTAMPTZ,
+            outcome TEXT NOT NULL,
+            error TEXT,
+            fetched BIGINT NOT NULL DEFAULT 0,
+            inserted BIGINT NOT NULL DEFAULT 0,
+            updated BIGINT NOT NULL DEFAULT 0,
+            unchanged BIGINT NOT NULL DEFAULT 0,
+            upstream_ms BIGINT
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_sync_runs_job ON sync_runs(job, started_at DESC)")
+        .execute(pool)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_sync_runs_started ON sync_runs(started_at DESC)")
+        .execute(pool)
+        .await?;
+
+    // ошибки, записанные до маскирования api_key в URL
+    sqlx::query(
+        "UPDATE sync_runs SET error = regexp_replace(error, 'api_key=[^&#\\s)]*', 'api_key=REDACTED', 'g')
+         WHERE error ~ 'api_key=(?!REDACTED)'"
+    )
+    .execute(pool)
+    .await?;
+
+    // настройки задач из /jobs (пауза, интервал или cron, jitter)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS job_settings(
+            name TEXT PRIMARY KEY,
+            paused BOOLEAN NOT NULL DEFAULT FALSE,
+            interval_seconds BIGINT,
+            cron TEXT,
+            jitter_seconds BIGINT,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // распределённые блокировки задач (см. lock.rs): имена advisory-ключей и аренды
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scheduler_lock_keys(
+            key BIGINT PRIMARY KEY,
+            name TEXT NOT NULL
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scheduler_leases(
+            name TEXT PRIMARY KEY,
+            holder TEXT NOT NULL,
+            token UUID NOT NULL,
+            acquired_at TIMESTAMPTZ NOT NULL,
+            expires_at TIMESTAMPTZ NOT NULL
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // кто что держит: advisory-блокировки из pg_locks (64-битный ключ = classid << 32 | objid)
+    // и действующие аренды. Соединение блокировки открывается под неё, поэтому
+    // backend_start - время захвата; holder - из application_name "rust_iss <instance> lock".
+    sqlx::query(
+        "CREATE OR REPLACE VIEW scheduler_locks AS
+         SELECT k.name, 'session'::TEXT AS mode,
+                COALESCE(substring(a.application_name FROM '^rust_iss (.*) lock$'), a.application_name) AS holder,
+                l.pid, a.client_addr::TEXT AS client_addr, a.backend_start AS acquired_at,
+                NULL::TIMESTAMPTZ AS expires_at
+         FROM pg_locks l
+         JOIN scheduler_lock_keys k ON k.key = ((l.classid::BIGINT << 32) | l.objid::BIGINT)
+         LEFT JOIN pg_stat_activity a ON a.pid = l.pid
+         WHERE l.locktype = 'advisory' AND l.objsubid = 1 AND l.granted
+         UNION ALL
+         SELECT name, 'lease', holder, NULL, NULL, acquired_at, expires_at
+         FROM scheduler_leases
+         WHERE expires_at > now()"
+    )
+    .execute(pool)
+    .await?;
+
     // универсальный кэш космоданных
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS space_cache(
@@ -70,152 +429,31 @@ async fn init_db(pool: &sqlx::PgPool) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn run_scheduler(state: AppState) {
-    let intervals = state.config.fetch_intervals.clone();
-    
-    // OSDR scheduler с advisory lock
-    {
-        let st = state.clone();
-        let osdr_interval = intervals.osdr;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "osdr", || async {
-                    let service = services::OsdrService::new(st.pool.clone(), st.http_client.clone());
-                    service.sync().await.map_err(|e| anyhow::anyhow!("{}", e))
-                }).await {
-                    error!("osdr scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(osdr_interval)).await;
-            }
-        });
-    }
-    
-    // ISS scheduler
-    {
-        let st = state.clone();
-        let iss_interval = intervals.iss;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "iss", || async {
-                    let service = services::IssService::new(st.pool.clone(), st.http_client.clone());
-                    service.fetch_and_store(&st.config.where_iss_url).await.map_err(|e| anyhow::anyhow!("{}", e))
-                }).await {
-                    error!("iss scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(iss_interval)).await;
-            }
-        });
-    }
-    
-    // APOD scheduler
-    {
-        let st = state.clone();
-        let apod_interval = intervals.apod;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "apod", || async {
-                    let service = services::SpaceCacheService::new(st.pool.clone(), st.http_client.clone());
-                    service.fetch_apod().await.map_err(|e| anyhow::anyhow!("{}", e))
-                }).await {
-                    error!("apod scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(apod_interval)).await;
-            }
-        });
-    }
-    
-    // NeoWs scheduler
-    {
-        let st = state.clone();
-        let neo_interval = intervals.neo;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "neo", || async {
-                    let service = services::SpaceCacheService::new(st.pool.clone(), st.http_client.clone());
-                    service.fetch_neo().await.map_err(|e| anyhow::anyhow!("{}", e))
-                }).await {
-                    error!("neo scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(neo_interval)).await;
-            }
-        });
-    }
-    
-    // DONKI scheduler
-    {
-        let st = state.clone();
-        let donki_interval = intervals.donki;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "donki", || async {
-                    let service = services::SpaceCacheService::new(st.pool.clone(), st.http_client.clone());
-                    service.fetch_donki_flr().await.map_err(|e| anyhow::anyhow!("{}", e))?;
-                    service.fetch_donki_cme().await.map_err(|e| anyhow::anyhow!("{}", e))?;
-                    Ok(())
-                }).await {
-                    error!("donki scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(donki_interval)).await;
-            }
-        });
-    }
-    
-    // SpaceX scheduler
-    {
-        let st = state.clone();
-        let spacex_interval = intervals.spacex;
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = run_with_lock(&st, "spacex", || async {
-                    let service = services::SpaceCacheService::new(st.pool.clone(), st.http_client.clone());
-                    service.fetch_spacex().await.map_err(|e| anyhow::anyhow!("{}", e))
-                }).await {
-                    error!("spacex scheduler error: {:?}", e);
-                }
-                tokio::time::sleep(Duration::from_secs(spacex_interval)).await;
-            }
-        });
-    }
-}
-
-async fn run_with_lock<F, Fut>(
-    state: &AppState,
-    lock_name: &str,
-    task: F,
-) -> anyhow::Result<()>
-where
-    F: FnOnce() -> Fut,
-    Fut: std::future::Future<Output = anyhow::Result<()>>,
-{
-    // Используем PostgreSQL advisory lock для защиты от наложения задач
-    let lock_id = {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        lock_name.hash(&mut hasher);
-        hasher.finish() as i64
+async fn seed_satellites(pool: &sqlx::PgPool, config: &Config) -> anyhow::Result<()> {
+    let iss = domain::Satellite {
+        norad_id: domain::ISS_NORAD_ID,
+        name: "ISS (ZARYA)".to_string(),
+        position_url: Some(config.where_iss_url.clone()),
+        tle_url: Some(config.where_iss_tle_url.clone()),
+        fetch_every_seconds: config.fetch_intervals.iss as i64,
+        tle_every_seconds: config.fetch_intervals.tle as i64,
+        enabled: true,
     };
-    
-    // Пытаемся получить блокировку (неблокирующий режим)
-    let lock_acquired: Option<bool> = sqlx::query_scalar(
-        "SELECT pg_try_advisory_lock($1)"
-    )
-    .bind(lock_id)
-    .fetch_optional(&state.pool)
-    .await?;
-    
-    if lock_acquired.unwrap_or(false) {
-        let result = task().await;
-        // Освобождаем блокировку
-        let _ = sqlx::query("SELECT pg_advisory_unlock($1)")
-            .bind(lock_id)
-            .execute(&state.pool)
-            .await;
-        result
-    } else {
-        info!("Skipping {} task - already running", lock_name);
-        Ok(())
+    repo::SatelliteRepo::seed(pool, &iss).await?;
+
+    for (norad_id, name) in &config.extra_satellites {
+        let sat = domain::Satellite {
+            norad_id: *norad_id,
+            name: name.clone(),
+            position_url: None,
+            tle_url: Some(config.sat_tle_url_template.replace("{norad_id}", &norad_id.to_string())),
+            fetch_every_seconds: config.fetch_intervals.iss as i64,
+            tle_every_seconds: config.fetch_intervals.tle as i64,
+            enabled: true,
+        };
+        repo::SatelliteRepo::seed(pool, &sat).await?;
     }
+    Ok(())
 }
 
 #[tokio::main]
@@ -230,26 +468,145 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env()?;
     let pool = PgPoolOptions::new()
         .max_connections(5)
-        .connect(&config.database_url)
+        .connect_with(
+            config
+                .database_url
+                .parse::<PgConnectOptions>()?
+                .application_name(&format!("rust_iss {}", config.instance_id)),
+        )
         .await?;
     
     init_db(&pool).await?;
 
+    seed_satellites(&pool, &config).await?;
+
+    match repo::IssRepo::backfill_positions(&pool).await {
+        Ok((filled, failed)) if filled + failed > 0 => {
+            info!("iss_fetch_log backfill: {} rows parsed, {} unparseable", filled, failed)
+        }
+        Ok(_) => {}
+        Err(e) => error!("iss_fetch_log backfill error: {}", e),
+    }
+
+    // Без файлов границ сервис работает (если не GEO_REQUIRED), но регион не проставляется
+    let regions = match geo::RegionIndex::load(
+        std::path::Path::new(&config.geo_countries_path),
+        std::path::Path::new(&config.geo_marine_path),
+    ) {
+        Ok(index) => {
+            info!("region index: {} polygons loaded", index.len());
+            match repo::IssRepo::backfill_regions(&pool, &index).await {
+                Ok(filled) if filled > 0 => info!("iss_fetch_log region backfill: {} rows", filled),
+                Ok(_) => {}
+                Err(e) => error!("iss_fetch_log region backfill error: {}", e),
+            }
+            index
+        }
+        Err(e) if config.geo_required => {
+            anyhow::bail!("region index not loaded from {} (GEO_REQUIRED is set): {}", config.geo_countries_path, e);
+        }
+        Err(e) => {
+            error!(
+                "region index not loaded from {}: {}; /iss/over and region fields will be empty \
+                 (put Natural Earth files into data/, see data/fetch.sh)",
+                config.geo_countries_path, e
+            );
+            geo::RegionIndex::empty()
+        }
+    };
+
     let http_client = clients::HttpClient::new(config.clone())?;
     
+    let registry = jobs::JobRegistry::load(&pool, &config).await?;
+    info!("scheduler: {} jobs", registry.jobs().len());
+
+    // одна реплика становится ведущей сразу, до первых запусков задач
+    let leader = leader::LeaderElection::new(pool.clone(), &config);
+    if let Err(e) = leader.try_acquire().await {
+        error!("leader election error: {}", e);
+    }
+
+    let (events, _) = tokio::sync::broadcast::channel(256);
     let state = AppState {
         pool: pool.clone(),
         http_client,
         config: config.clone(),
+        events,
+        regions: std::sync::Arc::new(regions),
+        jobs: std::sync::Arc::new(registry),
+        leader: std::sync::Arc::new(leader),
+        shutdown: CancellationToken::new(),
     };
 
-    // Запускаем фоновые задачи
-    run_scheduler(state.clone());
+    // Запускаем фоновые задачи; по расписанию их выполняет только ведущая реплика
+    let election = tokio::spawn({
+        let leader = state.leader.clone();
+        let shutdown = state.shutdown.clone();
+        async move { leader.run(shutdown).await }
+    });
+    state.jobs.spawn_all(&state);
+    tokio::spawn(events::relay(state.clone()));
 
-    let app = create_router().with_state(state);
+    let app = create_router().with_state(state.clone());
 
     let listener = tokio::net::TcpListener::bind(("0.0.0.0", 3000)).await?;
     info!("rust_iss listening on 0.0.0.0:3000");
-    axum::serve(listener, app.into_make_service()).await?;
+    tokio::spawn(wait_for_signal(state.shutdown.clone()));
+    let server = axum::serve(listener, app.into_make_service())
+        .with_graceful_shutdown(state.shutdown.clone().cancelled_owned());
+    let mut server = tokio::spawn(async move { server.await });
+
+    tokio::select! {
+        res = &mut server => {
+            state.shutdown.cancel();
+            res??;
+            return Ok(());
+        }
+        _ = state.shutdown.cancelled() => {}
+    }
+
+    // Новые соединения не принимаются, SSE/WebSocket закрываются, циклы задач
+    // не начинают новых запусков; текущие запуски дорабатывают до срока
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(config.shutdown_timeout_secs);
+    info!("shutting down, waiting up to {}s for running jobs", config.shutdown_timeout_secs);
+    state.jobs.drain(deadline).await;
+    let _ = election.await;
+    state.leader.release().await;
+    if tokio::time::timeout_at(deadline, &mut server).await.is_err() {
+        warn!("http connections still open at shutdown deadline");
+        server.abort();
+    }
+    pool.close().await;
+    info!("shutdown complete");
     Ok(())
 }
+
+// SIGTERM (docker stop) или Ctrl+C
+async fn wait_for_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("ctrl-c handler error: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                error!("SIGTERM handler error: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("received Ctrl+C"),
+        _ = terminate => info!("received SIGTERM"),
+    }
+    shutdown.cancel();
+}
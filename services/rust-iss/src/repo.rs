@@ -1,61 +1,700 @@
-use crate::domain::{IssLog, OsdrItem, SpaceCache};
+use crate::domain::{
+    IssHistoryBucket, IssLog, IssPosition, IssTle, JobSettings, NewObserverLocation, ObserverLocation, OsdrDetailFailure, OsdrItem, OsdrItemDetail, OsdrItemVersion, OsdrListQuery,
+    OsdrSearchHit, OsdrSort, PositionEvent, ProximityAlert,
+    RegionCrossing, RunStats, Satellite, SpaceCache, SyncRun, SyncState,
+};
+use crate::geo::{RegionHit, RegionIndex};
 use crate::error::ApiError;
+use crate::events::{JOB_SETTINGS_CHANNEL, POSITIONS_CHANNEL};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
 
 pub struct IssRepo;
 
+const ISS_COLUMNS: &str = "id, norad_id, fetched_at, source_url, payload, latitude, longitude, altitude_km,
+     velocity_kmh, visibility, footprint_km, solar_lat, solar_lon, observed_at, region, region_kind";
+
+fn iss_log_from_row(row: &PgRow) -> IssLog {
+    let position = match (
+        row.get::<Option<f64>, _>("latitude"),
+        row.get::<Option<f64>, _>("longitude"),
+        row.get::<Option<DateTime<Utc>>, _>("observed_at"),
+    ) {
+        (Some(latitude), Some(longitude), Some(timestamp)) => Some(IssPosition {
+            latitude,
+            longitude,
+            altitude_km: row.get::<Option<f64>, _>("altitude_km").unwrap_or_default(),
+            velocity_kmh: row.get::<Option<f64>, _>("velocity_kmh").unwrap_or_default(),
+            visibility: row.get("visibility"),
+            footprint_km: row.get("footprint_km"),
+            solar_lat: row.get("solar_lat"),
+            solar_lon: row.get("solar_lon"),
+            timestamp,
+        }),
+        _ => None,
+    };
+    IssLog {
+        id: row.get("id"),
+        norad_id: row.get("norad_id"),
+        fetched_at: row.get("fetched_at"),
+        source_url: row.get("source_url"),
+        payload: row.get("payload"),
+        position,
+        region: match (row.get::<Option<String>, _>("region"), row.get::<Option<String>, _>("region_kind")) {
+            (Some(name), Some(kind)) => Some(RegionHit { name, kind }),
+            _ => None,
+        },
+    }
+}
+
 impl IssRepo {
-    pub async fn insert(pool: &PgPool, source_url: &str, payload: Value) -> Result<(), ApiError> {
-        sqlx::query(
-            "INSERT INTO iss_fetch_log (source_url, payload) VALUES ($1, $2)"
+    pub async fn insert(
+        pool: &PgPool,
+        norad_id: i32,
+        source_url: &str,
+        payload: Value,
+        pos: &IssPosition,
+        region: Option<&RegionHit>,
+    ) -> Result<bool, ApiError> {
+        // false - источник вернул ту же позицию, что и в прошлый раз (observed_at не сдвинулся)
+        let fresh: bool = sqlx::query_scalar(
+            "WITH prev AS (
+                 SELECT observed_at FROM iss_fetch_log WHERE norad_id = $1 ORDER BY id DESC LIMIT 1
+             )
+             INSERT INTO iss_fetch_log (norad_id, source_url, payload, latitude, longitude, altitude_km,
+                 velocity_kmh, visibility, footprint_km, solar_lat, solar_lon, observed_at, region, region_kind)
+             VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)
+             RETURNING NOT EXISTS (SELECT 1 FROM prev WHERE prev.observed_at = $12)"
         )
+        .bind(norad_id)
         .bind(source_url)
         .bind(payload)
-        .execute(pool)
+        .bind(pos.latitude)
+        .bind(pos.longitude)
+        .bind(pos.altitude_km)
+        .bind(pos.velocity_kmh)
+        .bind(&pos.visibility)
+        .bind(pos.footprint_km)
+        .bind(pos.solar_lat)
+        .bind(pos.solar_lon)
+        .bind(pos.timestamp)
+        .bind(region.map(|r| &r.name))
+        .bind(region.map(|r| &r.kind))
+        .fetch_one(pool)
         .await?;
+        Ok(fresh)
+    }
+
+    // Новая позиция для стримов всех реплик (см. events::relay)
+    pub async fn notify_position(pool: &PgPool, event: &PositionEvent) -> Result<(), ApiError> {
+        let payload = serde_json::to_string(event).map_err(|e| ApiError::Internal(e.to_string()))?;
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(POSITIONS_CHANNEL)
+            .bind(payload)
+            .execute(pool)
+            .await?;
         Ok(())
     }
 
-    pub async fn get_last(pool: &PgPool) -> Result<Option<IssLog>, ApiError> {
-        let row = sqlx::query(
-            "SELECT id, fetched_at, source_url, payload
+    pub async fn get_last(pool: &PgPool, norad_id: i32) -> Result<Option<IssLog>, ApiError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ISS_COLUMNS} FROM iss_fetch_log WHERE norad_id = $1 ORDER BY id DESC LIMIT 1"
+        ))
+        .bind(norad_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.as_ref().map(iss_log_from_row))
+    }
+
+    pub async fn get_last_two(pool: &PgPool, norad_id: i32) -> Result<Vec<IssLog>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {ISS_COLUMNS} FROM iss_fetch_log WHERE norad_id = $1 ORDER BY id DESC LIMIT 2"
+        ))
+        .bind(norad_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(iss_log_from_row).collect())
+    }
+
+    // Только строки с разобранной позицией
+    pub async fn get_range(
+        pool: &PgPool,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<IssLog>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {ISS_COLUMNS} FROM iss_fetch_log
+             WHERE norad_id = $1 AND fetched_at BETWEEN $2 AND $3 AND latitude IS NOT NULL
+             ORDER BY fetched_at ASC
+             LIMIT $4"
+        ))
+        .bind(norad_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(iss_log_from_row).collect())
+    }
+
+    pub async fn get_nearest(
+        pool: &PgPool,
+        norad_id: i32,
+        at: DateTime<Utc>,
+        within_secs: i64,
+    ) -> Result<Option<IssLog>, ApiError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ISS_COLUMNS} FROM iss_fetch_log
+             WHERE norad_id = $3
+               AND fetched_at BETWEEN $1 - make_interval(secs => $2) AND $1 + make_interval(secs => $2)
+               AND latitude IS NOT NULL
+             ORDER BY abs(extract(epoch FROM fetched_at - $1)) LIMIT 1"
+        ))
+        .bind(at)
+        .bind(within_secs as f64)
+        .bind(norad_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.as_ref().map(iss_log_from_row))
+    }
+
+    // Keyset-пагинация по id; every > 1 - каждая N-я строка диапазона.
+    // Нумерация строк считается по всему диапазону, поэтому страницы стабильны.
+    pub async fn history(
+        pool: &PgPool,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        cursor: i64,
+        limit: i64,
+        every: i64,
+    ) -> Result<Vec<IssLog>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {ISS_COLUMNS} FROM (
+                 SELECT *, row_number() OVER (ORDER BY id) AS rn
+                 FROM iss_fetch_log
+                 WHERE norad_id = $1 AND fetched_at BETWEEN $2 AND $3 AND latitude IS NOT NULL
+             ) t
+             WHERE (rn - 1) % $4 = 0 AND id > $5
+             ORDER BY id
+             LIMIT $6"
+        ))
+        .bind(norad_id)
+        .bind(from)
+        .bind(to)
+        .bind(every)
+        .bind(cursor)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(iss_log_from_row).collect())
+    }
+
+    // Усреднение по интервалам date_trunc; долгота - круговое среднее,
+    // чтобы интервал на антимеридиане не усреднялся в 0°
+    pub async fn history_buckets(
+        pool: &PgPool,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        unit: &str,
+        cursor: i64,
+        limit: i64,
+    ) -> Result<Vec<IssHistoryBucket>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT date_trunc($4, fetched_at) AS bucket,
+                    count(*) AS samples,
+                    max(id) AS last_id,
+                    avg(latitude) AS latitude,
+                    degrees(atan2(avg(sin(radians(longitude))), avg(cos(radians(longitude))))) AS longitude,
+                    avg(altitude_km) AS altitude_km,
+                    avg(velocity_kmh) AS velocity_kmh
              FROM iss_fetch_log
-             ORDER BY id DESC LIMIT 1"
+             WHERE norad_id = $1 AND fetched_at BETWEEN $2 AND $3 AND latitude IS NOT NULL AND id > $5
+             GROUP BY bucket
+             ORDER BY bucket
+             LIMIT $6"
         )
-        .fetch_optional(pool)
+        .bind(norad_id)
+        .bind(from)
+        .bind(to)
+        .bind(unit)
+        .bind(cursor)
+        .bind(limit)
+        .fetch_all(pool)
         .await?;
 
-        if let Some(row) = row {
-            Ok(Some(IssLog {
-                id: row.get("id"),
-                fetched_at: row.get("fetched_at"),
-                source_url: row.get("source_url"),
-                payload: row.get("payload"),
-            }))
-        } else {
-            Ok(None)
+        Ok(rows
+            .into_iter()
+            .map(|r| IssHistoryBucket {
+                bucket: r.get("bucket"),
+                samples: r.get("samples"),
+                last_id: r.get("last_id"),
+                latitude: r.get("latitude"),
+                longitude: r.get("longitude"),
+                altitude_km: r.get("altitude_km"),
+                velocity_kmh: r.get("velocity_kmh"),
+            })
+            .collect())
+    }
+
+    // Разовая миграция: разбирает payload старых строк в типизированные колонки.
+    // Строки, которые разобрать не удалось, остаются с NULL и пропускаются;
+    // причина пишется в parse_error, и при следующих запусках они не перечитываются.
+    pub async fn backfill_positions(pool: &PgPool) -> Result<(u64, u64), ApiError> {
+        let mut last_id: i64 = 0;
+        let (mut filled, mut failed) = (0u64, 0u64);
+        loop {
+            let rows = sqlx::query(
+                "SELECT id, payload FROM iss_fetch_log
+                 WHERE latitude IS NULL AND parse_error IS NULL AND id > $1
+                 ORDER BY id LIMIT 500"
+            )
+            .bind(last_id)
+            .fetch_all(pool)
+            .await?;
+
+            if rows.is_empty() {
+                break;
+            }
+            for row in &rows {
+                let id: i64 = row.get("id");
+                last_id = id;
+                let payload: Value = row.get("payload");
+                let pos = match IssPosition::from_payload(&payload) {
+                    Ok(pos) => pos,
+                    Err(e) => {
+                        sqlx::query("UPDATE iss_fetch_log SET parse_error = $2 WHERE id = $1")
+                            .bind(id)
+                            .bind(e.to_string())
+                            .execute(pool)
+                            .await?;
+                        failed += 1;
+                        continue;
+                    }
+                };
+                sqlx::query(
+                    "UPDATE iss_fetch_log SET latitude=$2, longitude=$3, altitude_km=$4, velocity_kmh=$5,
+                         visibility=$6, footprint_km=$7, solar_lat=$8, solar_lon=$9, observed_at=$10
+                     WHERE id=$1"
+                )
+                .bind(id)
+                .bind(pos.latitude)
+                .bind(pos.longitude)
+                .bind(pos.altitude_km)
+                .bind(pos.velocity_kmh)
+                .bind(&pos.visibility)
+                .bind(pos.footprint_km)
+                .bind(pos.solar_lat)
+                .bind(pos.solar_lon)
+                .bind(pos.timestamp)
+                .execute(pool)
+                .await?;
+                filled += 1;
+            }
         }
+        Ok((filled, failed))
     }
 
-    pub async fn get_last_two(pool: &PgPool) -> Result<Vec<(DateTime<Utc>, Value)>, ApiError> {
+    // Смены региона, новые сверху. lag считается по всей истории до `to`,
+    // чтобы первая строка окна сравнивалась с предыдущей, а не с NULL.
+    pub async fn region_crossings(
+        pool: &PgPool,
+        norad_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RegionCrossing>, ApiError> {
         let rows = sqlx::query(
-            "SELECT fetched_at, payload FROM iss_fetch_log ORDER BY id DESC LIMIT 2"
+            "SELECT id, fetched_at, region, region_kind, from_region FROM (
+                 SELECT id, fetched_at, region, region_kind,
+                        lag(region) OVER (ORDER BY id) AS from_region
+                 FROM iss_fetch_log
+                 WHERE norad_id = $1 AND fetched_at <= $3 AND region IS NOT NULL
+             ) t
+             WHERE fetched_at >= $2 AND from_region IS DISTINCT FROM region
+             ORDER BY id DESC
+             LIMIT $4"
         )
+        .bind(norad_id)
+        .bind(from)
+        .bind(to)
+        .bind(limit)
         .fetch_all(pool)
         .await?;
 
         Ok(rows
             .into_iter()
-            .map(|r| (r.get("fetched_at"), r.get("payload")))
+            .map(|r| RegionCrossing {
+                id: r.get("id"),
+                at: r.get("fetched_at"),
+                region: r.get("region"),
+                kind: r.get("region_kind"),
+                from_region: r.get("from_region"),
+            })
             .collect())
     }
+
+    // Проставляет регион строкам, записанным до загрузки индекса
+    pub async fn backfill_regions(pool: &PgPool, index: &RegionIndex) -> Result<u64, ApiError> {
+        let mut last_id: i64 = 0;
+        let mut filled = 0u64;
+        loop {
+            let rows = sqlx::query(
+                "SELECT id, latitude, longitude FROM iss_fetch_log
+                 WHERE region IS NULL AND latitude IS NOT NULL AND id > $1
+                 ORDER BY id LIMIT 500"
+            )
+            .bind(last_id)
+            .fetch_all(pool)
+            .await?;
+
+            if rows.is_empty() {
+                break;
+            }
+            for row in &rows {
+                let id: i64 = row.get("id");
+                last_id = id;
+                let Some(hit) = index.locate(row.get("latitude"), row.get("longitude")) else {
+                    continue;
+                };
+                sqlx::query("UPDATE iss_fetch_log SET region=$2, region_kind=$3 WHERE id=$1")
+                    .bind(id)
+                    .bind(&hit.name)
+                    .bind(&hit.kind)
+                    .execute(pool)
+                    .await?;
+                filled += 1;
+            }
+        }
+        Ok(filled)
+    }
+}
+
+pub struct SatelliteRepo;
+
+fn satellite_from_row(r: &PgRow) -> Satellite {
+    Satellite {
+        norad_id: r.get("norad_id"),
+        name: r.get("name"),
+        position_url: r.get("position_url"),
+        tle_url: r.get("tle_url"),
+        fetch_every_seconds: r.get::<i32, _>("fetch_every_seconds") as i64,
+        tle_every_seconds: r.get::<i32, _>("tle_every_seconds") as i64,
+        enabled: r.get("enabled"),
+    }
+}
+
+impl SatelliteRepo {
+    // Существующие строки не трогаем: после первого старта реестр - источник правды
+    pub async fn seed(pool: &PgPool, sat: &Satellite) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO satellites(norad_id, name, position_url, tle_url,
+                 fetch_every_seconds, tle_every_seconds, enabled)
+             VALUES($1,$2,$3,$4,$5,$6,$7)
+             ON CONFLICT (norad_id) DO NOTHING"
+        )
+        .bind(sat.norad_id)
+        .bind(&sat.name)
+        .bind(&sat.position_url)
+        .bind(&sat.tle_url)
+        .bind(sat.fetch_every_seconds as i32)
+        .bind(sat.tle_every_seconds as i32)
+        .bind(sat.enabled)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list(pool: &PgPool) -> Result<Vec<Satellite>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT norad_id, name, position_url, tle_url, fetch_every_seconds, tle_every_seconds, enabled
+             FROM satellites ORDER BY norad_id"
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.iter().map(satellite_from_row).collect())
+    }
+
+    pub async fn list_enabled(pool: &PgPool) -> Result<Vec<Satellite>, ApiError> {
+        Ok(Self::list(pool).await?.into_iter().filter(|s| s.enabled).collect())
+    }
+
+    pub async fn get(pool: &PgPool, norad_id: i32) -> Result<Option<Satellite>, ApiError> {
+        let row = sqlx::query(
+            "SELECT norad_id, name, position_url, tle_url, fetch_every_seconds, tle_every_seconds, enabled
+             FROM satellites WHERE norad_id = $1"
+        )
+        .bind(norad_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.as_ref().map(satellite_from_row))
+    }
+}
+
+pub struct TleRepo;
+
+impl TleRepo {
+    pub async fn insert(
+        pool: &PgPool,
+        source_url: &str,
+        norad_id: i32,
+        name: Option<String>,
+        line1: &str,
+        line2: &str,
+        epoch: DateTime<Utc>,
+    ) -> Result<bool, ApiError> {
+        let res = sqlx::query(
+            "INSERT INTO iss_tle_log(source_url, norad_id, name, line1, line2, epoch)
+             VALUES($1,$2,$3,$4,$5,$6)
+             ON CONFLICT (line1, line2) DO NOTHING"
+        )
+        .bind(source_url)
+        .bind(norad_id)
+        .bind(name)
+        .bind(line1)
+        .bind(line2)
+        .bind(epoch)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // TLE с эпохой, ближайшей к моменту `at`
+    pub async fn get_nearest(
+        pool: &PgPool,
+        norad_id: i32,
+        at: DateTime<Utc>,
+    ) -> Result<Option<IssTle>, ApiError> {
+        let row = sqlx::query(
+            "SELECT id, fetched_at, source_url, name, line1, line2, epoch
+             FROM iss_tle_log
+             WHERE norad_id = $2
+             ORDER BY abs(extract(epoch FROM epoch - $1)) LIMIT 1"
+        )
+        .bind(at)
+        .bind(norad_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|r| IssTle {
+            id: r.get("id"),
+            fetched_at: r.get("fetched_at"),
+            source_url: r.get("source_url"),
+            name: r.get("name"),
+            line1: r.get("line1"),
+            line2: r.get("line2"),
+            epoch: r.get("epoch"),
+        }))
+    }
+}
+
+pub struct AlertRepo;
+
+fn location_from_row(r: &PgRow) -> ObserverLocation {
+    ObserverLocation {
+        id: r.get("id"),
+        name: r.get("name"),
+        latitude: r.get("latitude"),
+        longitude: r.get("longitude"),
+        radius_km: r.get("radius_km"),
+        norad_id: r.get("norad_id"),
+        webhook_url: r.get("webhook_url"),
+        created_at: r.get("created_at"),
+    }
+}
+
+fn alert_from_row(r: &PgRow) -> ProximityAlert {
+    ProximityAlert {
+        id: r.get("id"),
+        location_id: r.get("location_id"),
+        location_name: r.get("location_name"),
+        norad_id: r.get("norad_id"),
+        log_id: r.get("log_id"),
+        created_at: r.get("created_at"),
+        latitude: r.get("latitude"),
+        longitude: r.get("longitude"),
+        distance_km: r.get("distance_km"),
+        delivered_at: r.get("delivered_at"),
+        delivery_error: r.get("delivery_error"),
+    }
+}
+
+const ALERT_COLUMNS: &str = "a.id, a.location_id, l.name AS location_name, a.norad_id, a.log_id, a.created_at,
+     a.latitude, a.longitude, a.distance_km, a.delivered_at, a.delivery_error";
+
+impl AlertRepo {
+    pub async fn insert_location(
+        pool: &PgPool,
+        loc: &NewObserverLocation,
+        radius_km: f64,
+        norad_id: i32,
+    ) -> Result<ObserverLocation, ApiError> {
+        let row = sqlx::query(
+            "INSERT INTO observer_locations(name, latitude, longitude, radius_km, norad_id, webhook_url)
+             VALUES($1,$2,$3,$4,$5,$6)
+             RETURNING id, name, latitude, longitude, radius_km, norad_id, webhook_url, created_at"
+        )
+        .bind(&loc.name)
+        .bind(loc.latitude)
+        .bind(loc.longitude)
+        .bind(radius_km)
+        .bind(norad_id)
+        .bind(&loc.webhook_url)
+        .fetch_one(pool)
+        .await?;
+        Ok(location_from_row(&row))
+    }
+
+    pub async fn list_locations(pool: &PgPool, norad_id: Option<i32>) -> Result<Vec<ObserverLocation>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT id, name, latitude, longitude, radius_km, norad_id, webhook_url, created_at
+             FROM observer_locations
+             WHERE $1::INTEGER IS NULL OR norad_id = $1
+             ORDER BY id"
+        )
+        .bind(norad_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.iter().map(location_from_row).collect())
+    }
+
+    pub async fn delete_location(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
+        let res = sqlx::query("DELETE FROM observer_locations WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    // None - уведомление по этой записи уже было (повторная проверка той же пары)
+    pub async fn insert_alert(
+        pool: &PgPool,
+        loc: &ObserverLocation,
+        log_id: i64,
+        pos: &IssPosition,
+        distance_km: f64,
+    ) -> Result<Option<ProximityAlert>, ApiError> {
+        let id: Option<i64> = sqlx::query_scalar(
+            "INSERT INTO proximity_alerts(location_id, norad_id, log_id, latitude, longitude, distance_km)
+             VALUES($1,$2,$3,$4,$5,$6)
+             ON CONFLICT (location_id, log_id) DO NOTHING
+             RETURNING id"
+        )
+        .bind(loc.id)
+        .bind(loc.norad_id)
+        .bind(log_id)
+        .bind(pos.latitude)
+        .bind(pos.longitude)
+        .bind(distance_km)
+        .fetch_optional(pool)
+        .await?;
+
+        match id {
+            Some(id) => Self::get_alert(pool, id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_alert(pool: &PgPool, id: i64) -> Result<Option<ProximityAlert>, ApiError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ALERT_COLUMNS} FROM proximity_alerts a
+             JOIN observer_locations l ON l.id = a.location_id
+             WHERE a.id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.as_ref().map(alert_from_row))
+    }
+
+    pub async fn mark_delivery(pool: &PgPool, id: i64, error: Option<String>) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE proximity_alerts
+             SET delivered_at = CASE WHEN $2::TEXT IS NULL THEN now() END, delivery_error = $2
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_alerts(
+        pool: &PgPool,
+        location_id: Option<i64>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<ProximityAlert>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {ALERT_COLUMNS} FROM proximity_alerts a
+             JOIN observer_locations l ON l.id = a.location_id
+             WHERE ($1::BIGINT IS NULL OR a.location_id = $1)
+               AND ($2::TIMESTAMPTZ IS NULL OR a.created_at >= $2)
+             ORDER BY a.id DESC
+             LIMIT $3"
+        ))
+        .bind(location_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.iter().map(alert_from_row).collect())
+    }
 }
 
 pub struct OsdrRepo;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+const OSDR_FILTER: &str = "($1::TEXT IS NULL OR status = $1)
+               AND ($2::TIMESTAMPTZ IS NULL OR updated_at >= $2)
+               AND ($3::TEXT IS NULL OR strpos(lower(title), lower($3)) > 0)
+               AND ($4::BOOLEAN IS NULL OR (removed_at IS NOT NULL) = $4)";
+
+const OSDR_COLUMNS: &str = "id, dataset_id, title, status, updated_at, inserted_at, removed_at, raw_hash, raw";
+
+fn osdr_item_from_row(r: &PgRow) -> OsdrItem {
+    OsdrItem {
+        id: r.get("id"),
+        dataset_id: r.get("dataset_id"),
+        title: r.get("title"),
+        status: r.get("status"),
+        updated_at: r.get("updated_at"),
+        inserted_at: r.get("inserted_at"),
+        removed_at: r.get("removed_at"),
+        raw_hash: r.get("raw_hash"),
+        raw: r.get("raw"),
+    }
+}
+
+// NULL-ы заменяются, чтобы keyset-сравнение по кортежу было определено
+fn osdr_sort_expr(sort: OsdrSort) -> &'static str {
+    match sort {
+        OsdrSort::UpdatedAt => "COALESCE(updated_at, '-infinity'::TIMESTAMPTZ)",
+        OsdrSort::Title => "COALESCE(title, '')",
+        OsdrSort::InsertedAt => "inserted_at",
+    }
+}
+
 impl OsdrRepo {
+    // Содержимое сравнивается по md5 канонического текста jsonb: неизменённая
+    // строка не трогается, а каждая новая редакция пишется в osdr_item_versions.
     pub async fn upsert(
         pool: &PgPool,
         dataset_id: Option<String>,
@@ -63,63 +702,333 @@ impl OsdrRepo {
         status: Option<String>,
         updated_at: Option<DateTime<Utc>>,
         raw: Value,
-    ) -> Result<(), ApiError> {
-        if let Some(ds_id) = &dataset_id {
+    ) -> Result<UpsertOutcome, ApiError> {
+        let Some(ds_id) = &dataset_id else {
             sqlx::query(
-                "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                 VALUES($1,$2,$3,$4,$5)
-                 ON CONFLICT (dataset_id) DO UPDATE
-                 SET title=EXCLUDED.title, status=EXCLUDED.status,
-                     updated_at=EXCLUDED.updated_at, raw=EXCLUDED.raw"
-            )
-            .bind(ds_id)
-            .bind(&title)
-            .bind(&status)
-            .bind(&updated_at)
-            .bind(&raw)
-            .execute(pool)
-            .await?;
-        } else {
-            sqlx::query(
-                "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                 VALUES($1,$2,$3,$4,$5)"
+                "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw, raw_hash)
+                 VALUES($1,$2,$3,$4,$5, md5($5::JSONB::TEXT))"
             )
             .bind::<Option<String>>(None)
             .bind(&title)
             .bind(&status)
-            .bind(&updated_at)
+            .bind(updated_at)
             .bind(&raw)
             .execute(pool)
             .await?;
-        }
-        Ok(())
+            return Ok(UpsertOutcome::Inserted);
+        };
+
+        let mut tx = pool.begin().await?;
+        let row = sqlx::query(
+            "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw, raw_hash)
+             VALUES($1,$2,$3,$4,$5, md5($5::JSONB::TEXT))
+             ON CONFLICT (dataset_id) WHERE dataset_id IS NOT NULL DO UPDATE
+             SET title=EXCLUDED.title, status=EXCLUDED.status,
+                 updated_at=EXCLUDED.updated_at, raw=EXCLUDED.raw, raw_hash=EXCLUDED.raw_hash
+             WHERE osdr_items.raw_hash IS DISTINCT FROM EXCLUDED.raw_hash
+             RETURNING (xmax = 0) AS inserted, raw_hash"
+        )
+        .bind(ds_id)
+        .bind(&title)
+        .bind(&status)
+        .bind(updated_at)
+        .bind(&raw)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(UpsertOutcome::Unchanged);
+        };
+        sqlx::query(
+            "INSERT INTO osdr_item_versions(dataset_id, version, raw_hash, title, status, updated_at, raw)
+             SELECT $1, coalesce(max(version), 0) + 1, $2, $3, $4, $5, $6
+             FROM osdr_item_versions WHERE dataset_id = $1"
+        )
+        .bind(ds_id)
+        .bind(row.get::<String, _>("raw_hash"))
+        .bind(&title)
+        .bind(&status)
+        .bind(updated_at)
+        .bind(&raw)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(if row.get("inserted") { UpsertOutcome::Inserted } else { UpsertOutcome::Updated })
+    }
+
+    // Снова увиденные после пометки датасеты возвращаются в активные
+    pub async fn restore_seen(pool: &PgPool, dataset_ids: &[String]) -> Result<u64, ApiError> {
+        let res = sqlx::query(
+            "UPDATE osdr_items SET removed_at = NULL
+             WHERE removed_at IS NOT NULL AND dataset_id = ANY($1)"
+        )
+        .bind(dataset_ids)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+
+    // Только после полного успешного обхода: всё, чего в нём не было, помечается удалённым
+    pub async fn mark_removed_except(pool: &PgPool, seen: &[String]) -> Result<u64, ApiError> {
+        let res = sqlx::query(
+            "UPDATE osdr_items SET removed_at = now()
+             WHERE dataset_id IS NOT NULL AND removed_at IS NULL AND NOT (dataset_id = ANY($1))"
+        )
+        .bind(seen)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
     }
 
-    pub async fn list(pool: &PgPool, limit: i64) -> Result<Vec<OsdrItem>, ApiError> {
+    // Редакции датасета, новые сверху
+    pub async fn versions(pool: &PgPool, dataset_id: &str, limit: i64) -> Result<Vec<OsdrItemVersion>, ApiError> {
         let rows = sqlx::query(
-            "SELECT id, dataset_id, title, status, updated_at, inserted_at, raw
-             FROM osdr_items
-             ORDER BY inserted_at DESC
-             LIMIT $1"
+            "SELECT dataset_id, version, raw_hash, title, status, updated_at, raw, recorded_at
+             FROM osdr_item_versions
+             WHERE dataset_id = $1
+             ORDER BY version DESC
+             LIMIT $2"
         )
+        .bind(dataset_id)
         .bind(limit)
         .fetch_all(pool)
         .await?;
 
         Ok(rows
             .into_iter()
-            .map(|r| OsdrItem {
-                id: r.get("id"),
+            .map(|r| OsdrItemVersion {
                 dataset_id: r.get("dataset_id"),
+                version: r.get("version"),
+                raw_hash: r.get("raw_hash"),
                 title: r.get("title"),
                 status: r.get("status"),
                 updated_at: r.get("updated_at"),
-                inserted_at: r.get("inserted_at"),
                 raw: r.get("raw"),
+                recorded_at: r.get("recorded_at"),
             })
             .collect())
     }
 
+    pub async fn list(pool: &PgPool, query: &OsdrListQuery) -> Result<Vec<OsdrItem>, ApiError> {
+        let expr = osdr_sort_expr(query.sort);
+        let (dir, cmp) = if query.descending { ("DESC", "<") } else { ("ASC", ">") };
+        let key_type = if query.sort == OsdrSort::Title { "TEXT" } else { "TIMESTAMPTZ" };
+
+        let rows = sqlx::query(&format!(
+            "SELECT {OSDR_COLUMNS}
+             FROM osdr_items
+             WHERE {OSDR_FILTER}
+               AND ($5::TEXT IS NULL OR ({expr}, id) {cmp} ($5::{key_type}, $6))
+             ORDER BY {expr} {dir}, id {dir}
+             LIMIT $7 OFFSET $8"
+        ))
+        .bind(&query.status)
+        .bind(query.updated_since)
+        .bind(&query.q)
+        .bind(query.removed)
+        .bind(query.after.as_ref().map(|(key, _)| key))
+        .bind(query.after.as_ref().map(|(_, id)| *id).unwrap_or(0))
+        .bind(query.limit)
+        .bind(query.offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(osdr_item_from_row).collect())
+    }
+
+    pub async fn get(pool: &PgPool, dataset_id: &str) -> Result<Option<OsdrItem>, ApiError> {
+        let row = sqlx::query(&format!("SELECT {OSDR_COLUMNS} FROM osdr_items WHERE dataset_id = $1"))
+            .bind(dataset_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(row.as_ref().map(osdr_item_from_row))
+    }
+
+    pub async fn get_detail(pool: &PgPool, dataset_id: &str) -> Result<Option<OsdrItemDetail>, ApiError> {
+        let row = sqlx::query(
+            "SELECT dataset_id, source_url, fetched_at, raw_hash, files, assays, samples, factors, document
+             FROM osdr_item_details WHERE dataset_id = $1"
+        )
+        .bind(dataset_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(|r| OsdrItemDetail {
+            dataset_id: r.get("dataset_id"),
+            source_url: r.get("source_url"),
+            fetched_at: r.get("fetched_at"),
+            raw_hash: r.get("raw_hash"),
+            files: r.get("files"),
+            assays: r.get("assays"),
+            samples: r.get("samples"),
+            factors: r.get("factors"),
+            document: r.get("document"),
+        }))
+    }
+
+    pub async fn save_detail(pool: &PgPool, d: &OsdrItemDetail) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO osdr_item_details(dataset_id, source_url, fetched_at, raw_hash,
+                 files, assays, samples, factors, document)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9)
+             ON CONFLICT (dataset_id) DO UPDATE
+             SET source_url=EXCLUDED.source_url, fetched_at=EXCLUDED.fetched_at, raw_hash=EXCLUDED.raw_hash,
+                 files=EXCLUDED.files, assays=EXCLUDED.assays, samples=EXCLUDED.samples,
+                 factors=EXCLUDED.factors, document=EXCLUDED.document"
+        )
+        .bind(&d.dataset_id)
+        .bind(&d.source_url)
+        .bind(d.fetched_at)
+        .bind(&d.raw_hash)
+        .bind(&d.files)
+        .bind(&d.assays)
+        .bind(&d.samples)
+        .bind(&d.factors)
+        .bind(&d.document)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_detail_failure(pool: &PgPool, dataset_id: &str) -> Result<Option<OsdrDetailFailure>, ApiError> {
+        let row = sqlx::query(
+            "SELECT dataset_id, raw_hash, error, failures, failed_at, retry_at
+             FROM osdr_detail_failures WHERE dataset_id = $1"
+        )
+        .bind(dataset_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(|r| OsdrDetailFailure {
+            dataset_id: r.get("dataset_id"),
+            raw_hash: r.get("raw_hash"),
+            error: r.get("error"),
+            failures: r.get("failures"),
+            failed_at: r.get("failed_at"),
+            retry_at: r.get("retry_at"),
+        }))
+    }
+
+    // Пауза base * 2^(failures-1), не больше max; при смене raw счёт заново
+    pub async fn save_detail_failure(
+        pool: &PgPool,
+        dataset_id: &str,
+        raw_hash: Option<&str>,
+        error: &str,
+        base_secs: i64,
+        max_secs: i64,
+    ) -> Result<OsdrDetailFailure, ApiError> {
+        let r = sqlx::query(
+            "WITH prev AS (
+                 SELECT CASE WHEN raw_hash IS NOT DISTINCT FROM $2 THEN failures ELSE 0 END AS n
+                 FROM osdr_detail_failures WHERE dataset_id = $1
+             ), next AS (
+                 SELECT COALESCE((SELECT n FROM prev), 0) + 1 AS n
+             )
+             INSERT INTO osdr_detail_failures(dataset_id, raw_hash, error, failures, failed_at, retry_at)
+             SELECT $1, $2, $3, n, now(),
+                    now() + least($4::BIGINT * power(2, least(n - 1, 30)), $5::BIGINT) * INTERVAL '1 second'
+             FROM next
+             ON CONFLICT (dataset_id) DO UPDATE
+             SET raw_hash = EXCLUDED.raw_hash, error = EXCLUDED.error, failures = EXCLUDED.failures,
+                 failed_at = EXCLUDED.failed_at, retry_at = EXCLUDED.retry_at
+             RETURNING dataset_id, raw_hash, error, failures, failed_at, retry_at"
+        )
+        .bind(dataset_id)
+        .bind(raw_hash)
+        .bind(error)
+        .bind(base_secs)
+        .bind(max_secs)
+        .fetch_one(pool)
+        .await?;
+        Ok(OsdrDetailFailure {
+            dataset_id: r.get("dataset_id"),
+            raw_hash: r.get("raw_hash"),
+            error: r.get("error"),
+            failures: r.get("failures"),
+            failed_at: r.get("failed_at"),
+            retry_at: r.get("retry_at"),
+        })
+    }
+
+    pub async fn clear_detail_failure(pool: &PgPool, dataset_id: &str) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM osdr_detail_failures WHERE dataset_id = $1")
+            .bind(dataset_id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // Количество строк под фильтрами, без учёта курсора и смещения
+    pub async fn count_filtered(pool: &PgPool, query: &OsdrListQuery) -> Result<i64, ApiError> {
+        let row = sqlx::query(&format!("SELECT count(*) AS c FROM osdr_items WHERE {OSDR_FILTER}"))
+            .bind(&query.status)
+            .bind(query.updated_since)
+            .bind(&query.q)
+            .bind(query.removed)
+            .fetch_one(pool)
+            .await?;
+        Ok(row.get::<i64, _>("c"))
+    }
+
+    // Ранжирование ts_rank_cd по взвешенному вектору (title > описание > организм/assay/факторы).
+    // all: синтаксис websearch (слова через AND, "фраза", -исключение, or);
+    // any: любое из слов, ранжирование по числу совпадений.
+    // total считается отдельно и не зависит от offset.
+    pub async fn search(
+        pool: &PgPool,
+        q: &str,
+        any: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<OsdrSearchHit>, i64), ApiError> {
+        let tsquery = osdr_tsquery(any);
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT count(*) FROM osdr_items, {tsquery} AS query WHERE search @@ query"
+        ))
+        .bind(q)
+        .fetch_one(pool)
+        .await?;
+        if total <= offset {
+            return Ok((Vec::new(), total));
+        }
+
+        // подсветка - символами из области частного использования, в HTML они
+        // превращаются в <mark> после экранирования текста (см. highlight_html)
+        let rows = sqlx::query(&format!(
+            "SELECT id, dataset_id, title, status, updated_at,
+                    ts_rank_cd(search, query) AS rank,
+                    ts_headline('english', coalesce(title, ''), query, $4 || ', HighlightAll=true') AS title_highlight,
+                    btrim(ts_headline('english', osdr_description(raw) || ' ' || osdr_facets(raw), query,
+                                      $4 || ', MaxFragments=2, MaxWords=30, MinWords=10'))
+                        AS snippet
+             FROM osdr_items, {tsquery} AS query
+             WHERE search @@ query
+             ORDER BY rank DESC, id DESC
+             LIMIT $2 OFFSET $3"
+        ))
+        .bind(q)
+        .bind(limit)
+        .bind(offset)
+        .bind(format!("StartSel={}, StopSel={}", HL_START, HL_STOP))
+        .fetch_all(pool)
+        .await?;
+
+        let hits = rows
+            .into_iter()
+            .map(|r| OsdrSearchHit {
+                id: r.get("id"),
+                dataset_id: r.get("dataset_id"),
+                title: r.get("title"),
+                status: r.get("status"),
+                updated_at: r.get("updated_at"),
+                rank: r.get("rank"),
+                title_highlight: highlight_html(r.get("title_highlight")),
+                snippet: highlight_html(r.get("snippet")),
+            })
+            .collect();
+        Ok((hits, total))
+    }
+
     pub async fn count(pool: &PgPool) -> Result<i64, ApiError> {
         let row = sqlx::query("SELECT count(*) AS c FROM osdr_items")
             .fetch_one(pool)
@@ -128,17 +1037,283 @@ impl OsdrRepo {
     }
 }
 
-pub struct CacheRepo;
+fn osdr_tsquery(any: bool) -> &'static str {
+    if any {
+        // plainto_tsquery соединяет лексемы только через &, замена безопасна;
+        // 'simple' не стеммит уже стеммированные лексемы повторно
+        "to_tsquery('simple', replace(plainto_tsquery('english', $1)::TEXT, '&', '|'))"
+    } else {
+        "websearch_to_tsquery('english', $1)"
+    }
+}
 
-impl CacheRepo {
-    pub async fn insert(pool: &PgPool, source: &str, payload: Value) -> Result<(), ApiError> {
-        sqlx::query("INSERT INTO space_cache(source, payload) VALUES ($1,$2)")
-            .bind(source)
-            .bind(payload)
+const HL_START: char = '\u{E000}';
+const HL_STOP: char = '\u{E001}';
+
+// Текст из OSDR экранируется целиком, разметка - только <mark> на местах меток ts_headline
+fn highlight_html(s: String) -> String {
+    let mut out = String::with_capacity(s.len() + 16);
+    for c in s.chars() {
+        match c {
+            HL_START => out.push_str("<mark>"),
+            HL_STOP => out.push_str("</mark>"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub struct SyncStateRepo;
+
+impl SyncStateRepo {
+    pub async fn get(pool: &PgPool, source: &str) -> Result<Option<SyncState>, ApiError> {
+        let row = sqlx::query(
+            "SELECT source, high_water, last_full_at, last_success_at FROM sync_state WHERE source = $1"
+        )
+        .bind(source)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(|r| SyncState {
+            source: r.get("source"),
+            high_water: r.get("high_water"),
+            last_full_at: r.get("last_full_at"),
+            last_success_at: r.get("last_success_at"),
+        }))
+    }
+
+    // Вызывается только после успешного обхода; last_full_at - если он был полным
+    pub async fn save_success(
+        pool: &PgPool,
+        source: &str,
+        high_water: Option<DateTime<Utc>>,
+        full_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO sync_state(source, high_water, last_full_at, last_success_at)
+             VALUES($1,$2,$3, now())
+             ON CONFLICT (source) DO UPDATE
+             SET high_water = EXCLUDED.high_water,
+                 last_full_at = coalesce(EXCLUDED.last_full_at, sync_state.last_full_at),
+                 last_success_at = EXCLUDED.last_success_at"
+        )
+        .bind(source)
+        .bind(high_water)
+        .bind(full_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+fn sync_run_from_row(r: &PgRow) -> SyncRun {
+    SyncRun {
+        id: r.get("id"),
+        job: r.get("job"),
+        started_at: r.get("started_at"),
+        finished_at: r.get("finished_at"),
+        outcome: r.get("outcome"),
+        error: r.get("error"),
+        fetched: r.get("fetched"),
+        inserted: r.get("inserted"),
+        updated: r.get("updated"),
+        unchanged: r.get("unchanged"),
+        upstream_ms: r.get("upstream_ms"),
+    }
+}
+
+pub struct SyncRunRepo;
+
+const SYNC_RUN_COLUMNS: &str = "id, job, started_at, finished_at, outcome, error,
+    fetched, inserted, updated, unchanged, upstream_ms";
+
+impl SyncRunRepo {
+    pub async fn start(pool: &PgPool, job: &str) -> Result<i64, ApiError> {
+        let id = sqlx::query_scalar("INSERT INTO sync_runs(job, outcome) VALUES($1, 'running') RETURNING id")
+            .bind(job)
+            .fetch_one(pool)
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn finish(
+        pool: &PgPool,
+        id: i64,
+        outcome: &str,
+        error: Option<&str>,
+        stats: &RunStats,
+        upstream_ms: Option<i64>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE sync_runs
+             SET finished_at = now(), outcome = $2, error = $3,
+                 fetched = $4, inserted = $5, updated = $6, unchanged = $7, upstream_ms = $8
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(outcome)
+        .bind(error)
+        .bind(stats.fetched as i64)
+        .bind(stats.inserted as i64)
+        .bind(stats.updated as i64)
+        .bind(stats.unchanged as i64)
+        .bind(upstream_ms)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // Задачу уже выполняет другой экземпляр
+    pub async fn skipped(pool: &PgPool, job: &str) -> Result<(), ApiError> {
+        sqlx::query("INSERT INTO sync_runs(job, outcome, finished_at) VALUES($1, 'skipped', now())")
+            .bind(job)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list(
+        pool: &PgPool,
+        job: Option<&str>,
+        outcome: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SyncRun>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SYNC_RUN_COLUMNS} FROM sync_runs
+             WHERE ($1::TEXT IS NULL OR job = $1)
+               AND ($2::TEXT IS NULL OR outcome = $2)
+             ORDER BY started_at DESC, id DESC
+             LIMIT $3"
+        ))
+        .bind(job)
+        .bind(outcome)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.iter().map(sync_run_from_row).collect())
+    }
+
+    // Последний запуск каждой задачи (с outcome - последний с таким результатом)
+    pub async fn latest_per_job(pool: &PgPool, outcome: Option<&str>) -> Result<Vec<SyncRun>, ApiError> {
+        let rows = sqlx::query(&format!(
+            "SELECT DISTINCT ON (job) {SYNC_RUN_COLUMNS} FROM sync_runs
+             WHERE ($1::TEXT IS NULL OR outcome = $1)
+             ORDER BY job, started_at DESC, id DESC"
+        ))
+        .bind(outcome)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.iter().map(sync_run_from_row).collect())
+    }
+
+    pub async fn prune(pool: &PgPool, job: &str, keep_days: i64) -> Result<u64, ApiError> {
+        let res = sqlx::query(
+            "DELETE FROM sync_runs WHERE job = $1 AND started_at < now() - $2::BIGINT * INTERVAL '1 day'"
+        )
+        .bind(job)
+        .bind(keep_days)
+        .execute(pool)
+        .await?;
+        Ok(res.rows_affected())
+    }
+}
+
+pub struct JobSettingsRepo;
+
+impl JobSettingsRepo {
+    pub async fn list(pool: &PgPool) -> Result<Vec<JobSettings>, ApiError> {
+        let rows = sqlx::query(
+            "SELECT name, paused, interval_seconds, cron, jitter_seconds, updated_at FROM job_settings ORDER BY name"
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| JobSettings {
+                name: r.get("name"),
+                paused: r.get("paused"),
+                interval_seconds: r.get("interval_seconds"),
+                cron: r.get("cron"),
+                jitter_seconds: r.get("jitter_seconds"),
+                updated_at: r.get("updated_at"),
+            })
+            .collect())
+    }
+
+    pub async fn set_paused(pool: &PgPool, name: &str, paused: bool) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO job_settings(name, paused) VALUES($1, $2)
+             ON CONFLICT (name) DO UPDATE SET paused = EXCLUDED.paused, updated_at = now()"
+        )
+        .bind(name)
+        .bind(paused)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // interval и cron взаимоисключающие: записывается то, что задано
+    pub async fn set_schedule(
+        pool: &PgPool,
+        name: &str,
+        interval_seconds: Option<i64>,
+        cron: Option<&str>,
+        jitter_seconds: Option<i64>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            "INSERT INTO job_settings(name, interval_seconds, cron, jitter_seconds) VALUES($1, $2, $3, $4)
+             ON CONFLICT (name) DO UPDATE
+             SET interval_seconds = CASE WHEN $2::BIGINT IS NULL AND $3::TEXT IS NULL
+                                         THEN job_settings.interval_seconds ELSE $2 END,
+                 cron = CASE WHEN $2::BIGINT IS NULL AND $3::TEXT IS NULL
+                             THEN job_settings.cron ELSE $3 END,
+                 jitter_seconds = COALESCE($4, job_settings.jitter_seconds),
+                 updated_at = now()"
+        )
+        .bind(name)
+        .bind(interval_seconds)
+        .bind(cron)
+        .bind(jitter_seconds)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // Остальные реплики перечитывают job_settings (см. events::relay)
+    pub async fn notify(pool: &PgPool, name: &str) -> Result<(), ApiError> {
+        sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(JOB_SETTINGS_CHANNEL)
+            .bind(name)
             .execute(pool)
             .await?;
         Ok(())
     }
+}
+
+pub struct CacheRepo;
+
+impl CacheRepo {
+    // Запись добавляется всегда (fetched_at - время опроса); false - ответ
+    // совпал с прошлой записью источника
+    pub async fn insert(pool: &PgPool, source: &str, payload: Value) -> Result<bool, ApiError> {
+        let changed: bool = sqlx::query_scalar(
+            "WITH prev AS (
+                 SELECT payload FROM space_cache WHERE source = $1 ORDER BY id DESC LIMIT 1
+             )
+             INSERT INTO space_cache(source, payload) VALUES ($1,$2)
+             RETURNING NOT EXISTS (SELECT 1 FROM prev WHERE prev.payload = $2)"
+        )
+        .bind(source)
+        .bind(payload)
+        .fetch_one(pool)
+        .await?;
+        Ok(changed)
+    }
 
     pub async fn get_latest(pool: &PgPool, source: &str) -> Result<Option<SpaceCache>, ApiError> {
         let row = sqlx::query(
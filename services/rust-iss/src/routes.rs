@@ -1,4 +1,4 @@
-use axum::routing::get;
+use axum::routing::{delete, get, post};
 use axum::Router;
 use crate::app_state::AppState;
 use crate::handlers;
@@ -9,8 +9,39 @@ pub fn create_router() -> Router<AppState> {
         .route("/last", get(handlers::last_iss))
         .route("/fetch", get(handlers::trigger_iss))
         .route("/iss/trend", get(handlers::iss_trend))
+        .route("/iss/position", get(handlers::iss_position))
+        .route("/iss/passes", get(handlers::iss_passes))
+        .route("/iss/track", get(handlers::iss_track))
+        .route("/iss/history", get(handlers::iss_history))
+        .route("/iss/over", get(handlers::iss_over))
+        .route("/iss/stream", get(handlers::iss_stream_sse))
+        .route("/iss/stream/ws", get(handlers::iss_stream_ws))
+        .route("/sat", get(handlers::satellites))
+        .route("/sat/:norad_id/last", get(handlers::sat_last))
+        .route("/sat/:norad_id/fetch", get(handlers::sat_fetch))
+        .route("/sat/:norad_id/trend", get(handlers::sat_trend))
+        .route("/sat/:norad_id/position", get(handlers::sat_position))
+        .route("/sat/:norad_id/passes", get(handlers::sat_passes))
+        .route("/sat/:norad_id/track", get(handlers::sat_track))
+        .route("/sat/:norad_id/history", get(handlers::sat_history))
+        .route("/sat/:norad_id/over", get(handlers::sat_over))
+        .route("/sat/:norad_id/stream", get(handlers::sat_stream_sse))
+        .route("/sat/:norad_id/stream/ws", get(handlers::sat_stream_ws))
+        .route("/earth/terminator", get(handlers::earth_terminator))
+        .route("/alerts", get(handlers::alerts))
+        .route("/alerts/locations", get(handlers::alert_locations).post(handlers::alert_location_create))
+        .route("/alerts/locations/:id", delete(handlers::alert_location_delete))
         .route("/osdr/sync", get(handlers::osdr_sync))
         .route("/osdr/list", get(handlers::osdr_list))
+        .route("/osdr/search", get(handlers::osdr_search))
+        .route("/osdr/:dataset_id", get(handlers::osdr_detail))
+        .route("/osdr/:dataset_id/history", get(handlers::osdr_history))
+        .route("/jobs", get(handlers::jobs_list))
+        .route("/jobs/runs", get(handlers::job_runs))
+        .route("/jobs/:name", get(handlers::job_get).patch(handlers::job_update))
+        .route("/jobs/:name/run", post(handlers::job_run))
+        .route("/jobs/:name/pause", post(handlers::job_pause))
+        .route("/jobs/:name/resume", post(handlers::job_resume))
         .route("/space/:src/latest", get(handlers::space_latest))
         .route("/space/refresh", get(handlers::space_refresh))
         .route("/space/summary", get(handlers::space_summary))
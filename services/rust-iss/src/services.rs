@@ -1,33 +1,427 @@
-use crate::clients::HttpClient;
-use crate::domain::IssTrend;
+use crate::astro;
+use crate::clients::{redacted, same_origin, with_query_param, HttpClient};
+use crate::domain::{
+    num, HistoryStep, IssObservedSample, IssOrbitState, IssPass, IssPosition, IssTrend, IssTrendStats,
+    NewObserverLocation, ObserverLocation, OsdrItem, OsdrItemDetail, OsdrListQuery, OsdrSearchHit, OsdrSort, OsdrSyncStats,
+    JobPatch, JobSettings, PositionEvent, ProximityAlert, Satellite, SyncRun, ISS_NORAD_ID,
+};
 use crate::error::ApiError;
-use crate::repo::{CacheRepo, IssRepo, OsdrRepo};
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use crate::geo::{RegionHit, RegionIndex};
+use crate::config::Config;
+use crate::jobs::{JobHandle, JobRegistry, MAX_SCHEDULE_SECS};
+use crate::leader::LeaderElection;
+use crate::repo::{AlertRepo, CacheRepo, IssRepo, JobSettingsRepo, OsdrRepo, SatelliteRepo, SyncRunRepo, SyncStateRepo, TleRepo, UpsertOutcome};
+use crate::sgp4::{Sgp4, Tle};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
 use serde_json::Value;
 use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+// предел выборки для /iss/trend?window=: 7 дней опросов раз в 30 с
+const TREND_MAX_SAMPLES: i64 = 20160;
+
+// Сервис позиций спутника; по умолчанию - МКС (NORAD 25544)
 
 pub struct IssService {
     pool: PgPool,
     client: HttpClient,
+    norad_id: i32,
+    regions: Option<Arc<RegionIndex>>,
 }
 
 impl IssService {
     pub fn new(pool: PgPool, client: HttpClient) -> Self {
-        Self { pool, client }
+        Self::for_satellite(pool, client, ISS_NORAD_ID)
+    }
+
+    pub fn for_satellite(pool: PgPool, client: HttpClient, norad_id: i32) -> Self {
+        Self { pool, client, norad_id, regions: None }
+    }
+
+    // Индекс границ для обогащения новых записей регионом
+    pub fn with_regions(mut self, regions: Arc<RegionIndex>) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    fn locate(&self, pos: &IssPosition) -> Option<RegionHit> {
+        self.regions.as_ref()?.locate(pos.latitude, pos.longitude)
+    }
+
+    pub async fn satellite(&self) -> Result<Satellite, ApiError> {
+        SatelliteRepo::get(&self.pool, self.norad_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("satellite {} is not registered", self.norad_id)))
+    }
+
+    pub async fn list_satellites(&self) -> Result<Vec<Satellite>, ApiError> {
+        SatelliteRepo::list(&self.pool).await
+    }
+
+    // Без position_url позиция считается по последнему TLE. Новая позиция
+    // публикуется в шину (стримы /iss/stream на всех репликах); false - источник
+    // вернул прежнюю
+    pub async fn fetch_and_store(&self, sat: &Satellite) -> Result<bool, ApiError> {
+        let (pos, fresh) = match &sat.position_url {
+            Some(url) => {
+                let json = self.client.get_with_retry(url).await?;
+                // битый ответ отклоняем до записи
+                let pos = IssPosition::from_payload(&json)?;
+                let fresh = IssRepo::insert(&self.pool, sat.norad_id, url, json, &pos, self.locate(&pos).as_ref()).await?;
+                (pos, fresh)
+            }
+            None => {
+                let now = Utc::now();
+                let state = orbit_state(&self.propagator_for(now).await?, now)?;
+                let pos = position_from_state(&state);
+                let payload = serde_json::to_value(&state).unwrap_or_default();
+                let fresh = IssRepo::insert(&self.pool, sat.norad_id, "sgp4", payload, &pos, self.locate(&pos).as_ref()).await?;
+                (pos, fresh)
+            }
+        };
+        if fresh {
+            let event = PositionEvent { norad_id: sat.norad_id, interpolated: false, position: pos };
+            // запись уже сохранена; без события стримы догонят на следующем опросе
+            if let Err(e) = IssRepo::notify_position(&self.pool, &event).await {
+                tracing::warn!("position event for {} not published: {}", sat.norad_id, e);
+            }
+        }
+        Ok(fresh)
+    }
+
+    pub async fn get_trend_window(
+        &self,
+        window: Duration,
+        gap_threshold_sec: f64,
+    ) -> Result<IssTrendStats, ApiError> {
+        let to = Utc::now();
+        // на обрезанной выборке статистика врала бы молча - лишняя строка говорит о переполнении
+        let logs = IssRepo::get_range(&self.pool, self.norad_id, to - window, to, TREND_MAX_SAMPLES + 1).await?;
+        if logs.len() as i64 > TREND_MAX_SAMPLES {
+            return Err(ApiError::Validation(format!(
+                "window holds more than {TREND_MAX_SAMPLES} samples, narrow it"
+            )));
+        }
+
+        struct Sample {
+            at: DateTime<Utc>,
+            lat: f64,
+            lon: f64,
+            alt: Option<f64>,
+            velocity: Option<f64>,
+        }
+        let samples: Vec<Sample> = logs
+            .iter()
+            .filter_map(|l| {
+                let p = l.position.as_ref()?;
+                Some(Sample {
+                    at: p.timestamp,
+                    lat: p.latitude,
+                    lon: p.longitude,
+                    alt: Some(p.altitude_km),
+                    velocity: Some(p.velocity_kmh),
+                })
+            })
+            .collect();
+
+        let mut gaps = 0;
+        let mut max_gap_sec: f64 = 0.0;
+        let mut distance_km = 0.0;
+        let mut velocities = Vec::new();
+        let mut deviations = Vec::new();
+
+        for pair in samples.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let dt = (b.at - a.at).num_milliseconds() as f64 / 1000.0;
+            max_gap_sec = max_gap_sec.max(dt);
+            // через пропуск станция успевает сделать виток, расстояние теряет смысл
+            if dt > gap_threshold_sec {
+                gaps += 1;
+                continue;
+            }
+            if dt <= 0.0 {
+                continue;
+            }
+            let ground_km = haversine_km(a.lat, a.lon, b.lat, b.lon);
+            distance_km += ground_km;
+
+            // дуга по поверхности -> дуга на высоте орбиты
+            let alt = match (a.alt, b.alt) {
+                (Some(x), Some(y)) => (x + y) / 2.0,
+                _ => 0.0,
+            };
+            let v = ground_km * (EARTH_MEAN_RADIUS_KM + alt) / EARTH_MEAN_RADIUS_KM / dt * 3600.0;
+            velocities.push(v);
+            if let Some(reported) = b.velocity.filter(|r| *r > 0.0) {
+                deviations.push((v - reported).abs() / reported * 100.0);
+            }
+        }
+
+        let altitudes: Vec<f64> = samples.iter().filter_map(|s| s.alt).collect();
+        let reported: Vec<f64> = samples.iter().filter_map(|s| s.velocity).collect();
+
+        Ok(IssTrendStats {
+            window_sec: window.num_seconds(),
+            from_time: samples.first().map(|s| s.at),
+            to_time: samples.last().map(|s| s.at),
+            samples: samples.len(),
+            gaps,
+            max_gap_sec,
+            movement: distance_km > 0.1,
+            distance_km,
+            mean_velocity_kmh: mean(&velocities),
+            max_velocity_kmh: velocities.iter().cloned().reduce(f64::max),
+            reported_mean_velocity_kmh: mean(&reported),
+            velocity_deviation_pct: mean(&deviations),
+            altitude_min_km: altitudes.iter().cloned().reduce(f64::min),
+            altitude_max_km: altitudes.iter().cloned().reduce(f64::max),
+            altitude_mean_km: mean(&altitudes),
+        })
+    }
+
+    // Источник TLE: JSON wheretheiss.at ({line1, line2, header}) или текст 2LE/3LE
+    // true - TLE новый, false - такой уже сохранён
+    pub async fn fetch_tle_and_store(&self, sat: &Satellite) -> Result<bool, ApiError> {
+        let url = sat
+            .tle_url
+            .as_deref()
+            .ok_or_else(|| ApiError::Validation(format!("satellite {} has no tle_url", sat.norad_id)))?;
+        let body = self.client.get_text_with_retry(url).await?;
+        // проверяем TLE до записи, чтобы не хранить мусор
+        let tle = match serde_json::from_str::<Value>(&body) {
+            Ok(json) => {
+                let line1 = json["line1"].as_str().unwrap_or_default();
+                let line2 = json["line2"].as_str().unwrap_or_default();
+                let name = json["header"].as_str().or_else(|| json["name"].as_str());
+                Tle::parse(name, line1, line2)?
+            }
+            Err(_) => Tle::parse_text(&body)?,
+        };
+        if tle.norad_id as i32 != sat.norad_id {
+            return Err(ApiError::Validation(format!(
+                "TLE from {} is for satellite {}, expected {}",
+                url, tle.norad_id, sat.norad_id
+            )));
+        }
+        TleRepo::insert(&self.pool, url, sat.norad_id, tle.name, &tle.line1, &tle.line2, tle.epoch).await
+    }
+
+    pub async fn propagator_for(&self, at: DateTime<Utc>) -> Result<Sgp4, ApiError> {
+        let rec = TleRepo::get_nearest(&self.pool, self.norad_id, at)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("no TLE data".to_string()))?;
+        let tle = Tle::parse(rec.name.as_deref(), &rec.line1, &rec.line2)?;
+        Sgp4::new(&tle)
+    }
+
+    pub async fn position_at(&self, at: DateTime<Utc>) -> Result<IssOrbitState, ApiError> {
+        let sgp4 = self.propagator_for(at).await?;
+        let mut state = orbit_state(&sgp4, at)?;
+
+        // сверка с ближайшим опрошенным образцом (±10 минут)
+        let nearest = IssRepo::get_nearest(&self.pool, self.norad_id, at, 600).await?;
+        if let Some((id, pos)) = nearest.and_then(|l| Some((l.id, l.position?))) {
+            let p = orbit_state(&sgp4, pos.timestamp)?;
+            state.observed = Some(IssObservedSample {
+                id,
+                at: pos.timestamp,
+                latitude: pos.latitude,
+                longitude: pos.longitude,
+                altitude_km: pos.altitude_km,
+                velocity_kmh: pos.velocity_kmh,
+                delta_km: haversine_km(pos.latitude, pos.longitude, p.latitude, p.longitude),
+            });
+        }
+        Ok(state)
     }
 
-    pub async fn fetch_and_store(&self, url: &str) -> Result<(), ApiError> {
-        let json = self.client.get_iss().await?;
-        IssRepo::insert(&self.pool, url, json).await?;
-        Ok(())
+    pub async fn passes(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt_km: f64,
+        from: DateTime<Utc>,
+        days: i64,
+        min_elevation: f64,
+    ) -> Result<Vec<IssPass>, ApiError> {
+        let sgp4 = self.propagator_for(from).await?;
+        let elevation = |t: DateTime<Utc>| -> Result<astro::LookAngles, ApiError> {
+            let teme = sgp4.propagate_at(t)?;
+            Ok(astro::look_angles(lat, lon, alt_km, astro::teme_to_ecef(teme.position, t)))
+        };
+
+        // шаг 30 с: проход МКС длится минуты, пропустить его нельзя
+        let step = Duration::seconds(30);
+        let to = from + Duration::days(days);
+        // идущий в момент from проход: поиск начинается с его восхода
+        let mut t = from;
+        while elevation(t)?.elevation >= 0.0 && from - t < PASS_LOOKBACK {
+            t -= step;
+        }
+        let mut passes = Vec::new();
+        let mut prev = elevation(t)?.elevation;
+        let mut rise: Option<DateTime<Utc>> = None;
+        let mut peak: Option<(DateTime<Utc>, astro::LookAngles)> = None;
+
+        while t < to {
+            let next = t + step;
+            let look = elevation(next)?;
+
+            if prev < 0.0 && look.elevation >= 0.0 {
+                rise = Some(refine_crossing(&elevation, t, next)?);
+                peak = None;
+            }
+            if rise.is_some() && look.elevation >= 0.0 && peak.is_none_or(|(_, p)| look.elevation > p.elevation) {
+                peak = Some((next, look));
+            }
+            if prev >= 0.0 && look.elevation < 0.0 {
+                if let (Some(rise_time), Some((coarse, _))) = (rise, peak) {
+                    let set_time = refine_crossing(&elevation, t, next)?;
+                    let (culmination_time, top) = refine_peak(&elevation, coarse - step, coarse + step)?;
+                    if top.elevation >= min_elevation && set_time > from {
+                        let visible = visible_window(&sgp4, lat, lon, alt_km, rise_time, set_time)?;
+                        passes.push(IssPass {
+                            rise_time,
+                            rise_azimuth: elevation(rise_time)?.azimuth,
+                            culmination_time,
+                            culmination_azimuth: top.azimuth,
+                            culmination_range_km: top.range_km,
+                            max_elevation: top.elevation,
+                            set_time,
+                            set_azimuth: elevation(set_time)?.azimuth,
+                            duration_sec: (set_time - rise_time).num_seconds(),
+                            visible: visible.is_some(),
+                            visible_from: visible.map(|(a, _)| a),
+                            visible_to: visible.map(|(_, b)| b),
+                        });
+                    }
+                }
+                rise = None;
+                peak = None;
+            }
+
+            prev = look.elevation;
+            t = next;
+        }
+        Ok(passes)
+    }
+
+    pub async fn ground_track(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        predict_minutes: i64,
+    ) -> Result<Value, ApiError> {
+        let logs = IssRepo::get_range(&self.pool, self.norad_id, from, to, 20000).await?;
+        let observed: Vec<(DateTime<Utc>, f64, f64)> = logs
+            .iter()
+            .filter_map(|l| l.position.as_ref())
+            .map(|p| (p.timestamp, p.latitude, p.longitude))
+            .collect();
+
+        let mut features = track_features(&observed, "observed");
+
+        if predict_minutes > 0 {
+            let start = observed.last().map(|p| p.0).unwrap_or(to);
+            let sgp4 = self.propagator_for(start).await?;
+            let mut predicted = Vec::new();
+            let mut t = start;
+            while t <= start + Duration::minutes(predict_minutes) {
+                let st = orbit_state(&sgp4, t)?;
+                predicted.push((t, st.latitude, st.longitude));
+                t += Duration::seconds(30);
+            }
+            features.extend(track_features(&predicted, "predicted"));
+        }
+
+        Ok(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": features
+        }))
+    }
+
+    pub async fn history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        cursor: i64,
+        limit: i64,
+        step: &HistoryStep,
+    ) -> Result<Value, ApiError> {
+        let (items, next_cursor) = match step {
+            HistoryStep::Every(n) => {
+                let rows = IssRepo::history(&self.pool, self.norad_id, from, to, cursor, limit, *n).await?;
+                let next = rows.last().map(|r| r.id);
+                let items: Vec<Value> = rows
+                    .into_iter()
+                    .map(|r| {
+                        serde_json::json!({
+                            "id": r.id,
+                            "fetched_at": r.fetched_at,
+                            "illumination": r.position.as_ref().map(|p| p.illumination()),
+                            "region": r.region,
+                            "position": r.position,
+                        })
+                    })
+                    .collect();
+                (items, next)
+            }
+            HistoryStep::Bucket(unit) => {
+                let rows =
+                    IssRepo::history_buckets(&self.pool, self.norad_id, from, to, unit, cursor, limit).await?;
+                let next = rows.last().map(|r| r.last_id);
+                let items = rows
+                    .into_iter()
+                    .map(|r| serde_json::to_value(r).unwrap_or_default())
+                    .collect();
+                (items, next)
+            }
+        };
+        // неполная страница - дальше данных нет
+        let next_cursor = if (items.len() as i64) < limit { None } else { next_cursor };
+
+        Ok(serde_json::json!({
+            "norad_id": self.norad_id,
+            "from": from,
+            "to": to,
+            "count": items.len(),
+            "next_cursor": next_cursor,
+            "items": items
+        }))
+    }
+
+    // Текущий регион по последней записи и история пересечений границ.
+    // Записи без региона (до загрузки индекса) досчитываются на лету.
+    pub async fn over(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> Result<Value, ApiError> {
+        let last = IssRepo::get_last(&self.pool, self.norad_id).await?;
+        let current = last.as_ref().and_then(|log| {
+            let pos = log.position.as_ref()?;
+            let region = log.region.clone().or_else(|| self.locate(pos))?;
+            Some(serde_json::json!({
+                "name": region.name,
+                "kind": region.kind,
+                "latitude": pos.latitude,
+                "longitude": pos.longitude,
+                "fetched_at": log.fetched_at
+            }))
+        });
+        let crossings = IssRepo::region_crossings(&self.pool, self.norad_id, from, to, limit).await?;
+
+        Ok(serde_json::json!({
+            "norad_id": self.norad_id,
+            "current": current,
+            "crossings": crossings
+        }))
     }
 
     pub async fn get_last(&self) -> Result<Option<crate::domain::IssLog>, ApiError> {
-        IssRepo::get_last(&self.pool).await
+        IssRepo::get_last(&self.pool, self.norad_id).await
     }
 
     pub async fn get_trend(&self) -> Result<IssTrend, ApiError> {
-        let rows = IssRepo::get_last_two(&self.pool).await?;
+        let rows = IssRepo::get_last_two(&self.pool, self.norad_id).await?;
 
         if rows.len() < 2 {
             return Ok(IssTrend {
@@ -44,14 +438,16 @@ impl IssService {
             });
         }
 
-        let (t1, p1) = &rows[1];
-        let (t2, p2) = &rows[0];
+        let t1 = rows[1].fetched_at;
+        let t2 = rows[0].fetched_at;
+        let p1 = rows[1].position.as_ref();
+        let p2 = rows[0].position.as_ref();
 
-        let lat1 = num(&p1["latitude"]);
-        let lon1 = num(&p1["longitude"]);
-        let lat2 = num(&p2["latitude"]);
-        let lon2 = num(&p2["longitude"]);
-        let v2 = num(&p2["velocity"]);
+        let lat1 = p1.map(|p| p.latitude);
+        let lon1 = p1.map(|p| p.longitude);
+        let lat2 = p2.map(|p| p.latitude);
+        let lon2 = p2.map(|p| p.longitude);
+        let v2 = p2.map(|p| p.velocity_kmh);
 
         let mut delta_km = 0.0;
         let mut movement = false;
@@ -59,15 +455,15 @@ impl IssService {
             delta_km = haversine_km(a1, o1, a2, o2);
             movement = delta_km > 0.1;
         }
-        let dt_sec = (*t2 - *t1).num_milliseconds() as f64 / 1000.0;
+        let dt_sec = (t2 - t1).num_milliseconds() as f64 / 1000.0;
 
         Ok(IssTrend {
             movement,
             delta_km,
             dt_sec,
             velocity_kmh: v2,
-            from_time: Some(*t1),
-            to_time: Some(*t2),
+            from_time: Some(t1),
+            to_time: Some(t2),
             from_lat: lat1,
             from_lon: lon1,
             to_lat: lat2,
@@ -76,16 +472,251 @@ impl IssService {
     }
 }
 
-fn num(v: &Value) -> Option<f64> {
-    if let Some(x) = v.as_f64() {
-        return Some(x);
+fn position_from_state(state: &IssOrbitState) -> IssPosition {
+    IssPosition {
+        latitude: state.latitude,
+        longitude: state.longitude,
+        altitude_km: state.altitude_km,
+        velocity_kmh: state.velocity_kmh,
+        visibility: None,
+        footprint_km: None,
+        solar_lat: None,
+        solar_lon: None,
+        timestamp: state.at,
     }
-    if let Some(s) = v.as_str() {
-        return s.parse::<f64>().ok();
+}
+
+// Поток позиций для одного подключения: сначала последняя запись,
+// затем события шины. С интерполяцией между опросами раз в секунду
+// отдаются точки, посчитанные по SGP4.
+pub struct PositionStream {
+    service: IssService,
+    rx: broadcast::Receiver<PositionEvent>,
+    ticker: Option<tokio::time::Interval>,
+    sgp4: Option<Sgp4>,
+    // без TLE запрос к БД не повторяется каждый тик: пауза растёт после каждой неудачи
+    sgp4_retry_at: Option<DateTime<Utc>>,
+    sgp4_failures: u32,
+    started: bool,
+}
+
+// пауза перед повторной загрузкой TLE для интерполяции: 5 с, удваивается до 5 мин
+const STREAM_TLE_RETRY_BASE_SECS: i64 = 5;
+const STREAM_TLE_RETRY_MAX_SECS: i64 = 300;
+
+impl PositionStream {
+    pub fn new(service: IssService, rx: broadcast::Receiver<PositionEvent>, interpolate: bool) -> Self {
+        let ticker = interpolate.then(|| {
+            let mut t = tokio::time::interval(std::time::Duration::from_secs(1));
+            t.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            t
+        });
+        Self { service, rx, ticker, sgp4: None, sgp4_retry_at: None, sgp4_failures: 0, started: false }
     }
-    None
+
+    pub async fn next(&mut self) -> Option<PositionEvent> {
+        if !self.started {
+            self.started = true;
+            if let Ok(Some(position)) = self.service.get_last().await.map(|l| l.and_then(|l| l.position)) {
+                return Some(PositionEvent { norad_id: self.service.norad_id, interpolated: false, position });
+            }
+        }
+        loop {
+            let ticker = &mut self.ticker;
+            let tick = async {
+                match ticker {
+                    Some(t) => {
+                        t.tick().await;
+                    }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            tokio::select! {
+                r = self.rx.recv() => match r {
+                    Ok(ev) if ev.norad_id == self.service.norad_id => {
+                        // свежий опрос - перечитать TLE при следующей интерполяции
+                        self.sgp4 = None;
+                        return Some(ev);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                _ = tick => {
+                    let now = Utc::now();
+                    if self.sgp4.is_none() && self.sgp4_retry_at.is_none_or(|at| now >= at) {
+                        match self.service.propagator_for(now).await {
+                            Ok(sgp4) => {
+                                self.sgp4 = Some(sgp4);
+                                self.sgp4_failures = 0;
+                                self.sgp4_retry_at = None;
+                            }
+                            Err(_) => {
+                                let secs = (STREAM_TLE_RETRY_BASE_SECS << self.sgp4_failures.min(10))
+                                    .min(STREAM_TLE_RETRY_MAX_SECS);
+                                self.sgp4_failures += 1;
+                                self.sgp4_retry_at = Some(now + Duration::seconds(secs));
+                            }
+                        }
+                    }
+                    if let Some(state) = self.sgp4.as_ref().and_then(|s| orbit_state(s, now).ok()) {
+                        return Some(PositionEvent {
+                            norad_id: self.service.norad_id,
+                            interpolated: true,
+                            position: position_from_state(&state),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn orbit_state(sgp4: &Sgp4, at: DateTime<Utc>) -> Result<IssOrbitState, ApiError> {
+    let teme = sgp4.propagate_at(at)?;
+    let geo = astro::teme_to_geodetic(teme.position, at);
+    let v = teme.velocity;
+    Ok(IssOrbitState {
+        at,
+        latitude: geo.latitude,
+        longitude: geo.longitude,
+        altitude_km: geo.altitude_km,
+        velocity_kmh: (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt() * 3600.0,
+        tle_epoch: sgp4.epoch(),
+        tle_age_hours: (at - sgp4.epoch()).num_seconds() as f64 / 3600.0,
+        observed: None,
+    })
+}
+
+fn track_features(points: &[(DateTime<Utc>, f64, f64)], kind: &str) -> Vec<Value> {
+    split_antimeridian(points)
+        .into_iter()
+        .filter(|seg| seg.coordinates.len() >= 2)
+        .map(|seg| {
+            serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": seg.coordinates },
+                "properties": { "kind": kind, "from": seg.from, "to": seg.to }
+            })
+        })
+        .collect()
+}
+
+struct TrackSegment {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    coordinates: Vec<[f64; 2]>,
+}
+
+// Разрезает трек по антимеридиану: на каждом переходе через ±180°
+// отрезок закрывается интерполированной точкой на краю карты, а новый
+// начинается с зеркальной точки. Координаты в порядке GeoJSON [lon, lat].
+fn split_antimeridian(points: &[(DateTime<Utc>, f64, f64)]) -> Vec<TrackSegment> {
+    let mut segments = Vec::new();
+    let Some(&(first_t, _, _)) = points.first() else {
+        return segments;
+    };
+    let mut current = TrackSegment { from: first_t, to: first_t, coordinates: Vec::new() };
+    for (i, &(t, lat, lon)) in points.iter().enumerate() {
+        if i > 0 {
+            let (_, plat, plon) = points[i - 1];
+            if (lon - plon).abs() > 180.0 {
+                let edge = if plon > 0.0 { 180.0 } else { -180.0 };
+                let lon_unwrapped = if plon > 0.0 { lon + 360.0 } else { lon - 360.0 };
+                let k = (edge - plon) / (lon_unwrapped - plon);
+                let cross_lat = plat + (lat - plat) * k;
+                current.coordinates.push([edge, cross_lat]);
+                let next = TrackSegment { from: t, to: t, coordinates: vec![[-edge, cross_lat]] };
+                segments.push(std::mem::replace(&mut current, next));
+            }
+        }
+        current.coordinates.push([lon, lat]);
+        current.to = t;
+    }
+    segments.push(current);
+    segments
+}
+
+// насколько раньше from искать восход идущего прохода
+const PASS_LOOKBACK: Duration = Duration::hours(1);
+const PASS_VISIBILITY_STEP_SECS: i64 = 10;
+// наблюдатель в темноте: Солнце ниже гражданских сумерек
+const OBSERVER_DARK_SUN_ELEVATION: f64 = -6.0;
+
+type TimeWindow = (DateTime<Utc>, DateTime<Utc>);
+
+// Часть прохода, когда спутник освещён Солнцем (вне тени Земли, см. astro::shadow),
+// а у наблюдателя темно; None - проход невидим глазом
+fn visible_window(
+    sgp4: &Sgp4,
+    lat: f64,
+    lon: f64,
+    alt_km: f64,
+    rise: DateTime<Utc>,
+    set: DateTime<Utc>,
+) -> Result<Option<TimeWindow>, ApiError> {
+    let mut window: Option<TimeWindow> = None;
+    let mut t = rise;
+    while t <= set {
+        let sun = astro::sun_ecef(t);
+        let sat = astro::teme_to_ecef(sgp4.propagate_at(t)?.position, t);
+        let lit = astro::shadow(sat, sun) == astro::Shadow::Sunlit;
+        let dark = astro::look_angles(lat, lon, alt_km, sun).elevation < OBSERVER_DARK_SUN_ELEVATION;
+        if lit && dark {
+            window = Some((window.map_or(t, |(a, _)| a), t));
+        }
+        t += Duration::seconds(PASS_VISIBILITY_STEP_SECS);
+    }
+    Ok(window)
+}
+
+// Бисекция момента пересечения горизонта с точностью до секунды
+fn refine_crossing<F>(f: &F, mut lo: DateTime<Utc>, mut hi: DateTime<Utc>) -> Result<DateTime<Utc>, ApiError>
+where
+    F: Fn(DateTime<Utc>) -> Result<astro::LookAngles, ApiError>,
+{
+    let lo_up = f(lo)?.elevation >= 0.0;
+    while hi - lo > Duration::seconds(1) {
+        let mid = lo + (hi - lo) / 2;
+        if (f(mid)?.elevation >= 0.0) == lo_up {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(hi)
 }
 
+// Тернарный поиск максимума угла места
+fn refine_peak<F>(
+    f: &F,
+    mut lo: DateTime<Utc>,
+    mut hi: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, astro::LookAngles), ApiError>
+where
+    F: Fn(DateTime<Utc>) -> Result<astro::LookAngles, ApiError>,
+{
+    while hi - lo > Duration::seconds(2) {
+        let third = (hi - lo) / 3;
+        if f(lo + third)?.elevation < f(hi - third)?.elevation {
+            lo += third;
+        } else {
+            hi -= third;
+        }
+    }
+    let t = lo + (hi - lo) / 2;
+    Ok((t, f(t)?))
+}
+
+fn mean(xs: &[f64]) -> Option<f64> {
+    if xs.is_empty() {
+        None
+    } else {
+        Some(xs.iter().sum::<f64>() / xs.len() as f64)
+    }
+}
+
+const EARTH_MEAN_RADIUS_KM: f64 = 6371.0;
+
 fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let rlat1 = lat1.to_radians();
     let rlat2 = lat2.to_radians();
@@ -93,48 +724,644 @@ fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let dlon = (lon2 - lon1).to_radians();
     let a = (dlat / 2.0).sin().powi(2) + rlat1.cos() * rlat2.cos() * (dlon / 2.0).sin().powi(2);
     let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-    6371.0 * c
+    EARTH_MEAN_RADIUS_KM * c
+}
+
+// Уведомления о пролёте над точками наблюдения
+// шаг подспутниковой точки между двумя записями (~15 км для МКС)
+const ALERT_TRACK_STEP_SECS: i64 = 2;
+// при большем разрыве между записями (простой сервиса) проверяется только последняя
+const ALERT_TRACK_MAX_GAP_SECS: i64 = 900;
+// SGP4 дальше от опрошенной позиции (устаревший TLE) - трасса по дуге большого круга
+const ALERT_TRACK_MAX_SGP4_DELTA_KM: f64 = 50.0;
+
+pub struct AlertService {
+    pool: PgPool,
+    client: HttpClient,
+    default_radius_km: f64,
+    default_webhook: Option<String>,
+    webhook_allowlist: Vec<reqwest::Url>,
+}
+
+impl AlertService {
+    pub fn new(pool: PgPool, client: HttpClient, config: &Config) -> Self {
+        Self {
+            pool,
+            client,
+            default_radius_km: config.alert_radius_km,
+            default_webhook: config.alert_webhook_url.clone(),
+            webhook_allowlist: config.alert_webhook_allowlist.clone(),
+        }
+    }
+
+    // Вебхук точки - только ALERT_WEBHOOK_URL или адрес под префиксом из
+    // ALERT_WEBHOOK_ALLOWLIST (схема, хост и порт совпадают, путь начинается с префикса):
+    // точки создаются без авторизации, и произвольный адрес открыл бы запросы во внутреннюю сеть
+    fn webhook_allowed(&self, url: &str) -> bool {
+        if self.default_webhook.as_deref() == Some(url) {
+            return true;
+        }
+        let Ok(url) = reqwest::Url::parse(url) else {
+            return false;
+        };
+        self.webhook_allowlist.iter().any(|prefix| {
+            url.scheme() == prefix.scheme()
+                && url.host_str() == prefix.host_str()
+                && url.port_or_known_default() == prefix.port_or_known_default()
+                && url.path().starts_with(prefix.path())
+        })
+    }
+
+    pub async fn add_location(&self, loc: NewObserverLocation) -> Result<ObserverLocation, ApiError> {
+        if loc.name.trim().is_empty() {
+            return Err(ApiError::Validation("name is required".to_string()));
+        }
+        if !(-90.0..=90.0).contains(&loc.latitude) || !(-180.0..=180.0).contains(&loc.longitude) {
+            return Err(ApiError::Validation("coordinates out of range".to_string()));
+        }
+        let radius_km = loc.radius_km.unwrap_or(self.default_radius_km);
+        if !(radius_km > 0.0 && radius_km <= 5000.0) {
+            return Err(ApiError::Validation("radius_km must be in (0, 5000]".to_string()));
+        }
+        if let Some(url) = loc.webhook_url.as_deref().filter(|u| !self.webhook_allowed(u)) {
+            return Err(ApiError::Validation(format!(
+                "webhook_url {} is not allowed (see ALERT_WEBHOOK_ALLOWLIST)",
+                url
+            )));
+        }
+        let norad_id = loc.norad_id.unwrap_or(ISS_NORAD_ID);
+        if SatelliteRepo::get(&self.pool, norad_id).await?.is_none() {
+            return Err(ApiError::NotFound(format!("satellite {} is not registered", norad_id)));
+        }
+        AlertRepo::insert_location(&self.pool, &loc, radius_km, norad_id).await
+    }
+
+    pub async fn list_locations(&self) -> Result<Vec<ObserverLocation>, ApiError> {
+        AlertRepo::list_locations(&self.pool, None).await
+    }
+
+    pub async fn delete_location(&self, id: i64) -> Result<(), ApiError> {
+        if AlertRepo::delete_location(&self.pool, id).await? {
+            Ok(())
+        } else {
+            Err(ApiError::NotFound(format!("location {} not found", id)))
+        }
+    }
+
+    pub async fn list_alerts(
+        &self,
+        location_id: Option<i64>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<ProximityAlert>, ApiError> {
+        AlertRepo::list_alerts(&self.pool, location_id, since, limit).await
+    }
+
+    // Проверяет трассу между двумя последними записями: уведомление, если
+    // ближайшая к точке наблюдения подспутниковая точка внутри радиуса, а
+    // предыдущая запись - снаружи (пока спутник остаётся внутри, повторов нет).
+    // В уведомление попадает ближайшая точка трассы.
+    pub async fn check(&self, norad_id: i32) -> Result<Vec<ProximityAlert>, ApiError> {
+        let locations = AlertRepo::list_locations(&self.pool, Some(norad_id)).await?;
+        if locations.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = IssRepo::get_last_two(&self.pool, norad_id).await?;
+        let Some((log, pos)) = rows.first().and_then(|r| Some((r, r.position.as_ref()?))) else {
+            return Ok(Vec::new());
+        };
+        let prev = rows.get(1).and_then(|r| r.position.as_ref());
+        let track = match prev {
+            Some(prev) => self.ground_track(norad_id, prev, pos).await,
+            None => vec![pos.clone()],
+        };
+
+        let mut alerts = Vec::new();
+        for loc in &locations {
+            if prev.is_some_and(|p| haversine_km(loc.latitude, loc.longitude, p.latitude, p.longitude) <= loc.radius_km) {
+                continue;
+            }
+            let Some((closest, distance_km)) = track
+                .iter()
+                .map(|p| (p, haversine_km(loc.latitude, loc.longitude, p.latitude, p.longitude)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                continue;
+            };
+            if distance_km > loc.radius_km {
+                continue;
+            }
+            let Some(mut alert) = AlertRepo::insert_alert(&self.pool, loc, log.id, closest, distance_km).await? else {
+                continue;
+            };
+            tracing::info!("proximity alert: {} within {:.0} km of {}", norad_id, distance_km, loc.name);
+
+            if let Some(url) = loc.webhook_url.as_ref().or(self.default_webhook.as_ref()) {
+                let body = serde_json::json!({ "event": "proximity_alert", "alert": &alert });
+                // точки, созданные до ограничения, могут хранить недопустимый адрес
+                let error = if self.webhook_allowed(url) {
+                    self.client.post_json(url, &body).await.err().map(|e| e.to_string())
+                } else {
+                    Some("webhook url is not allowed".to_string())
+                };
+                AlertRepo::mark_delivery(&self.pool, alert.id, error.clone()).await?;
+                alert.delivered_at = error.is_none().then(Utc::now);
+                alert.delivery_error = error;
+            }
+            alerts.push(alert);
+        }
+        Ok(alerts)
+    }
+
+    // Подспутниковые точки после prev до cur включительно с шагом
+    // ALERT_TRACK_STEP_SECS: по SGP4, без TLE или при расхождении с опросом -
+    // по дуге большого круга
+    async fn ground_track(&self, norad_id: i32, prev: &IssPosition, cur: &IssPosition) -> Vec<IssPosition> {
+        let gap = (cur.timestamp - prev.timestamp).num_seconds();
+        if gap <= ALERT_TRACK_STEP_SECS || gap > ALERT_TRACK_MAX_GAP_SECS {
+            return vec![cur.clone()];
+        }
+        let times: Vec<DateTime<Utc>> = (1..)
+            .map(|i| prev.timestamp + Duration::seconds(i * ALERT_TRACK_STEP_SECS))
+            .take_while(|t| *t < cur.timestamp)
+            .collect();
+
+        let service = IssService::for_satellite(self.pool.clone(), self.client.clone(), norad_id);
+        let sgp4 = service.propagator_for(cur.timestamp).await.ok().filter(|sgp4| {
+            orbit_state(sgp4, cur.timestamp).is_ok_and(|s| {
+                haversine_km(s.latitude, s.longitude, cur.latitude, cur.longitude) <= ALERT_TRACK_MAX_SGP4_DELTA_KM
+            })
+        });
+        let mut track: Vec<IssPosition> = match sgp4 {
+            Some(sgp4) => times
+                .iter()
+                .filter_map(|t| orbit_state(&sgp4, *t).ok())
+                .map(|s| position_from_state(&s))
+                .collect(),
+            None => times
+                .iter()
+                .map(|t| {
+                    let f = (*t - prev.timestamp).num_milliseconds() as f64 / (gap * 1000) as f64;
+                    great_circle_point(prev, cur, f, *t)
+                })
+                .collect(),
+        };
+        track.push(cur.clone());
+        track
+    }
 }
 
+// Точка на дуге большого круга между a и b, f - доля пути (0..1)
+fn great_circle_point(a: &IssPosition, b: &IssPosition, f: f64, at: DateTime<Utc>) -> IssPosition {
+    let to_vec = |lat: f64, lon: f64| {
+        let (lat, lon) = (lat.to_radians(), lon.to_radians());
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    };
+    let va = to_vec(a.latitude, a.longitude);
+    let vb = to_vec(b.latitude, b.longitude);
+    let dot = (va[0] * vb[0] + va[1] * vb[1] + va[2] * vb[2]).clamp(-1.0, 1.0);
+    let omega = dot.acos();
+    let (ka, kb) = if omega < 1e-9 {
+        (1.0 - f, f)
+    } else {
+        (((1.0 - f) * omega).sin() / omega.sin(), (f * omega).sin() / omega.sin())
+    };
+    let v = [ka * va[0] + kb * vb[0], ka * va[1] + kb * vb[1], ka * va[2] + kb * vb[2]];
+    IssPosition {
+        latitude: v[2].atan2((v[0] * v[0] + v[1] * v[1]).sqrt()).to_degrees(),
+        longitude: v[1].atan2(v[0]).to_degrees(),
+        altitude_km: a.altitude_km + (b.altitude_km - a.altitude_km) * f,
+        velocity_kmh: b.velocity_kmh,
+        visibility: None,
+        footprint_km: None,
+        solar_lat: None,
+        solar_lon: None,
+        timestamp: at,
+    }
+}
+
+const OSDR_SOURCE: &str = "osdr";
+// пауза после неудачной загрузки карточки: 1 мин, удваивается до 6 ч
+const OSDR_DETAIL_RETRY_BASE_SECS: i64 = 60;
+const OSDR_DETAIL_RETRY_MAX_SECS: i64 = 6 * 3600;
+
 pub struct OsdrService {
     pool: PgPool,
     client: HttpClient,
+    base_url: String,
+    detail_url_template: String,
+    since_param: String,
+    full_every: Duration,
+    max_pages: u32,
 }
 
 impl OsdrService {
-    pub fn new(pool: PgPool, client: HttpClient) -> Self {
-        Self { pool, client }
+    pub fn new(pool: PgPool, client: HttpClient, config: &Config) -> Self {
+        Self {
+            pool,
+            client,
+            base_url: config.nasa_api_url.clone(),
+            detail_url_template: config.osdr_detail_url_template.clone(),
+            since_param: config.osdr_since_param.clone(),
+            full_every: Duration::hours(config.osdr_full_every_hours),
+            max_pages: config.osdr_max_pages,
+        }
     }
 
-    pub async fn sync(&self) -> Result<(), ApiError> {
-        let json = self.client.get_osdr().await?;
-        
-        let items = if let Some(a) = json.as_array() {
-            a.clone()
-        } else if let Some(v) = json.get("items").and_then(|x| x.as_array()) {
-            v.clone()
-        } else if let Some(v) = json.get("results").and_then(|x| x.as_array()) {
-            v.clone()
+    // Обход каталога по страницам до конца. Полный - раз в full_every или по
+    // запросу, иначе только обновлённые после high-water mark прошлого успешного
+    // обхода. Состояние сохраняется лишь при успехе: упавший обход повторится целиком.
+    pub async fn sync(&self, force_full: bool) -> Result<OsdrSyncStats, ApiError> {
+        let started = Utc::now();
+        let state = SyncStateRepo::get(&self.pool, OSDR_SOURCE).await?;
+        let high_water = state.as_ref().and_then(|s| s.high_water);
+        let full = force_full
+            || self.since_param.is_empty()
+            || high_water.is_none()
+            || state
+                .as_ref()
+                .and_then(|s| s.last_full_at)
+                .is_none_or(|t| started - t >= self.full_every);
+
+        let mut stats = OsdrSyncStats {
+            full,
+            since: if full { None } else { high_water },
+            ..Default::default()
+        };
+        let mut url = match stats.since {
+            Some(since) => with_query_param(&self.base_url, &self.since_param, &since.to_rfc3339()),
+            None => self.base_url.clone(),
+        };
+        let mut max_updated: Option<DateTime<Utc>> = None;
+        let mut seen: Vec<String> = Vec::new();
+        let mut visited = HashSet::new();
+
+        loop {
+            if !visited.insert(url.clone()) {
+                return Err(ApiError::Internal(format!("OSDR pagination loops back to {}", redacted(&url))));
+            }
+            if stats.pages >= self.max_pages {
+                return Err(ApiError::Internal(format!("OSDR crawl stopped after {} pages", stats.pages)));
+            }
+            let json = self.client.get_osdr(&url).await?;
+            stats.pages += 1;
+
+            let items = page_items(&json);
+            for item in &items {
+                let id = s_pick(item, &["dataset_id", "id", "uuid", "studyId", "accession", "osdr_id"]);
+                let title = s_pick(item, &["title", "name", "label"]);
+                let status = s_pick(item, &["status", "state", "lifecycle"]);
+                let updated = t_pick(item, &["updated", "updated_at", "modified", "lastUpdated", "timestamp"]);
+                if updated > max_updated {
+                    max_updated = updated;
+                }
+                if let Some(id) = &id {
+                    seen.push(id.clone());
+                }
+
+                match OsdrRepo::upsert(&self.pool, id, title, status, updated, item.clone()).await? {
+                    UpsertOutcome::Inserted => stats.inserted += 1,
+                    UpsertOutcome::Updated => stats.updated += 1,
+                    UpsertOutcome::Unchanged => stats.unchanged += 1,
+                }
+            }
+            stats.fetched += items.len() as u64;
+
+            match next_page_url(&json, &url, stats.fetched) {
+                Some(next) if !items.is_empty() => url = next,
+                _ => break,
+            }
+        }
+
+        stats.restored = OsdrRepo::restore_seen(&self.pool, &seen).await?;
+        // пустой полный ответ скорее сбой источника, чем опустевший каталог
+        if full && !seen.is_empty() {
+            stats.removed = OsdrRepo::mark_removed_except(&self.pool, &seen).await?;
+        }
+
+        // отметка - самая поздняя дата записи; если дат нет, полный обход
+        // сдвигает её на своё начало, инкрементальный оставляет прежней
+        stats.high_water = match max_updated {
+            Some(t) => Some(high_water.map_or(t, |hw| hw.max(t))),
+            None if full => Some(started),
+            None => high_water,
+        };
+        SyncStateRepo::save_success(&self.pool, OSDR_SOURCE, stats.high_water, full.then_some(started)).await?;
+        Ok(stats)
+    }
+
+    // Страница списка: total - под фильтрами, next_cursor - для полной страницы
+    pub async fn list(&self, query: &OsdrListQuery) -> Result<(Vec<OsdrItem>, i64, Option<String>), ApiError> {
+        let items = OsdrRepo::list(&self.pool, query).await?;
+        let total = OsdrRepo::count_filtered(&self.pool, query).await?;
+        let next_cursor = match items.last() {
+            Some(last) if items.len() as i64 == query.limit => {
+                let key = match query.sort {
+                    OsdrSort::UpdatedAt => last
+                        .updated_at
+                        .map(|t| t.to_rfc3339())
+                        .unwrap_or_else(|| "-infinity".to_string()),
+                    OsdrSort::Title => last.title.clone().unwrap_or_default(),
+                    OsdrSort::InsertedAt => last.inserted_at.to_rfc3339(),
+                };
+                Some(encode_cursor(query.sort, query.descending, &key, last.id))
+            }
+            _ => None,
+        };
+        Ok((items, total, next_cursor))
+    }
+
+    // Запись каталога с карточкой. Карточка грузится при первом запросе и
+    // перезапрашивается, если raw записи изменился; при сбое источника
+    // отдаётся запись с прежней карточкой (или без неё) и detail_error,
+    // а следующая попытка - не раньше detail_retry_at (refresh её не ждёт).
+    pub async fn detail(&self, dataset_id: &str, refresh: bool) -> Result<Value, ApiError> {
+        let item = OsdrRepo::get(&self.pool, dataset_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("dataset {} not found", dataset_id)))?;
+        let cached = OsdrRepo::get_detail(&self.pool, dataset_id).await?;
+
+        let stale = cached.as_ref().is_none_or(|d| d.raw_hash != item.raw_hash);
+        let backoff = if stale && !refresh {
+            OsdrRepo::get_detail_failure(&self.pool, dataset_id)
+                .await?
+                .filter(|f| f.raw_hash == item.raw_hash && f.retry_at > Utc::now())
         } else {
-            vec![json.clone()]
+            None
         };
+        let (detail, detail_error, retry_at) = match backoff {
+            Some(f) => (cached, Some(f.error), Some(f.retry_at)),
+            None if refresh || stale => match self.fetch_detail(&item, dataset_id).await {
+                Ok(d) => {
+                    OsdrRepo::save_detail(&self.pool, &d).await?;
+                    OsdrRepo::clear_detail_failure(&self.pool, dataset_id).await?;
+                    (Some(d), None, None)
+                }
+                Err(e) => {
+                    let f = OsdrRepo::save_detail_failure(
+                        &self.pool,
+                        dataset_id,
+                        item.raw_hash.as_deref(),
+                        &e.to_string(),
+                        OSDR_DETAIL_RETRY_BASE_SECS,
+                        OSDR_DETAIL_RETRY_MAX_SECS,
+                    )
+                    .await?;
+                    (cached, Some(f.error), Some(f.retry_at))
+                }
+            },
+            None => (cached, None, None),
+        };
+
+        Ok(serde_json::json!({
+            "id": item.id,
+            "dataset_id": item.dataset_id,
+            "title": item.title,
+            "status": item.status,
+            "updated_at": item.updated_at,
+            "inserted_at": item.inserted_at,
+            "removed_at": item.removed_at,
+            "raw": item.raw,
+            "detail": detail,
+            "detail_error": detail_error,
+            "detail_retry_at": retry_at
+        }))
+    }
 
-        for item in items {
-            let id = s_pick(&item, &["dataset_id", "id", "uuid", "studyId", "accession", "osdr_id"]);
-            let title = s_pick(&item, &["title", "name", "label"]);
-            let status = s_pick(&item, &["status", "state", "lifecycle"]);
-            let updated = t_pick(&item, &["updated", "updated_at", "modified", "lastUpdated", "timestamp"]);
-            
-            OsdrRepo::upsert(&self.pool, id, title, status, updated, item).await?;
+    async fn fetch_detail(&self, item: &OsdrItem, dataset_id: &str) -> Result<OsdrItemDetail, ApiError> {
+        // ссылка из записи каталога - только на тот же OSDR API, иначе по шаблону
+        let url = s_pick(&item.raw, &["REST_URL", "rest_url", "url"])
+            .filter(|u| same_origin(u, &self.detail_url_template) || same_origin(u, &self.base_url))
+            .unwrap_or_else(|| self.detail_url_template.replace("{dataset_id}", dataset_id));
+        let json = self.client.get_osdr(&url).await?;
+
+        // v2 отвечает {"OSD-1": {...}}
+        let doc = match json.as_object() {
+            Some(map) if map.len() == 1 && map.contains_key(dataset_id) => json[dataset_id].clone(),
+            _ => json,
+        };
+        let pick = |ptrs: &[&str]| {
+            ptrs.iter()
+                .find_map(|p| doc.pointer(p))
+                .filter(|v| !v.is_null())
+                .cloned()
+        };
+
+        Ok(OsdrItemDetail {
+            dataset_id: dataset_id.to_string(),
+            source_url: url.clone(),
+            fetched_at: Utc::now(),
+            raw_hash: item.raw_hash.clone(),
+            files: pick(&["/files", "/metadata/files"]),
+            assays: pick(&["/assays", "/metadata/assays", "/metadata/study assay technology type"]),
+            samples: pick(&["/samples", "/metadata/samples"]),
+            factors: pick(&["/factors", "/metadata/factors", "/metadata/study factor name"]),
+            document: doc,
+        })
+    }
+
+    // Редакции с диффом относительно предыдущей; у самой первой changes = null
+    pub async fn history(&self, dataset_id: &str, limit: i64, with_raw: bool) -> Result<Value, ApiError> {
+        // на одну больше, чтобы последней на странице было с чем сравнивать
+        let versions = OsdrRepo::versions(&self.pool, dataset_id, limit + 1).await?;
+        if versions.is_empty() {
+            return Err(ApiError::NotFound(format!("dataset {} not found", dataset_id)));
         }
-        Ok(())
+        let items: Vec<Value> = versions
+            .iter()
+            .take(limit as usize)
+            .enumerate()
+            .map(|(i, v)| {
+                let changes = versions.get(i + 1).map(|prev| {
+                    let mut ops = Vec::new();
+                    json_diff(&prev.raw, &v.raw, "", &mut ops);
+                    ops
+                });
+                serde_json::json!({
+                    "version": v.version,
+                    "recorded_at": v.recorded_at,
+                    "raw_hash": v.raw_hash,
+                    "title": v.title,
+                    "status": v.status,
+                    "updated_at": v.updated_at,
+                    "changes": changes,
+                    "raw": if with_raw { Some(&v.raw) } else { None },
+                })
+            })
+            .collect();
+
+        Ok(serde_json::json!({
+            "dataset_id": dataset_id,
+            "versions": items
+        }))
     }
 
-    pub async fn list(&self, limit: i64) -> Result<Vec<crate::domain::OsdrItem>, ApiError> {
-        OsdrRepo::list(&self.pool, limit).await
+    // any = None: сначала все слова, при пустом результате - любое из них.
+    // Возвращает фактически применённый режим.
+    pub async fn search(
+        &self,
+        q: &str,
+        any: Option<bool>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<OsdrSearchHit>, i64, bool), ApiError> {
+        let (hits, total) = OsdrRepo::search(&self.pool, q, any.unwrap_or(false), limit, offset).await?;
+        if any.is_none() && total == 0 {
+            let (hits, total) = OsdrRepo::search(&self.pool, q, true, limit, offset).await?;
+            return Ok((hits, total, true));
+        }
+        Ok((hits, total, any.unwrap_or(false)))
     }
 }
 
+// Дифф в духе RFC 6902 (пути - JSON Pointer) со старым значением для replace/remove.
+// Массивы сравниваются поэлементно по индексу.
+fn json_diff(old: &Value, new: &Value, path: &str, ops: &mut Vec<Value>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (k, av) in a {
+                let p = format!("{}/{}", path, k.replace('~', "~0").replace('/', "~1"));
+                match b.get(k) {
+                    Some(bv) => json_diff(av, bv, &p, ops),
+                    None => ops.push(serde_json::json!({ "op": "remove", "path": p, "old": av })),
+                }
+            }
+            for (k, bv) in b {
+                if !a.contains_key(k) {
+                    let p = format!("{}/{}", path, k.replace('~', "~0").replace('/', "~1"));
+                    ops.push(serde_json::json!({ "op": "add", "path": p, "value": bv }));
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let common = a.len().min(b.len());
+            for i in 0..common {
+                json_diff(&a[i], &b[i], &format!("{}/{}", path, i), ops);
+            }
+            for (i, bv) in b.iter().enumerate().skip(common) {
+                ops.push(serde_json::json!({ "op": "add", "path": format!("{}/{}", path, i), "value": bv }));
+            }
+            // удаление с конца: при последовательном применении индексы не сдвигаются
+            for (i, av) in a.iter().enumerate().skip(common).rev() {
+                ops.push(serde_json::json!({ "op": "remove", "path": format!("{}/{}", path, i), "old": av }));
+            }
+        }
+        _ if old != new => {
+            ops.push(serde_json::json!({ "op": "replace", "path": path, "old": old, "value": new }));
+        }
+        _ => {}
+    }
+}
+
+// Непрозрачный курсор: hex от JSON [sort, order, ключ, id], безопасен в query string
+fn encode_cursor(sort: OsdrSort, descending: bool, key: &str, id: i64) -> String {
+    let order = if descending { "desc" } else { "asc" };
+    serde_json::json!([sort.as_str(), order, key, id])
+        .to_string()
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Курсор действителен только для того же sort/order; ключ временной сортировки
+// проверяется здесь, чтобы ошибка была валидацией, а не сбоем запроса к БД
+pub fn decode_cursor(cursor: &str, sort: OsdrSort, descending: bool) -> Result<(String, i64), ApiError> {
+    let invalid = || ApiError::Validation("invalid cursor".to_string());
+    let bytes = cursor
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|h| h.len() == 2)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    let (cursor_sort, order, key, id): (String, String, String, i64) =
+        serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    let expected_order = if descending { "desc" } else { "asc" };
+    if cursor_sort != sort.as_str() || order != expected_order {
+        return Err(ApiError::Validation(format!(
+            "cursor was issued for sort={} order={}, not sort={} order={}",
+            cursor_sort,
+            order,
+            sort.as_str(),
+            expected_order
+        )));
+    }
+    let key_ok = match sort {
+        OsdrSort::Title => true,
+        OsdrSort::UpdatedAt => key == "-infinity" || DateTime::parse_from_rfc3339(&key).is_ok(),
+        OsdrSort::InsertedAt => DateTime::parse_from_rfc3339(&key).is_ok(),
+    };
+    if !key_ok {
+        return Err(invalid());
+    }
+    Ok((key, id))
+}
+
+// Записи страницы: массив в корне или под items/results/data/datasets,
+// либо карта {"OSD-1": {...}} (OSDR API v2), где ключ - идентификатор
+fn page_items(json: &Value) -> Vec<Value> {
+    if let Some(a) = json.as_array() {
+        return a.clone();
+    }
+    for key in ["items", "results", "data", "datasets"] {
+        if let Some(a) = json.get(key).and_then(|x| x.as_array()) {
+            return a.clone();
+        }
+    }
+    if let Some(map) = json.as_object() {
+        // пустая карта - пустая страница инкрементального запроса
+        if map.is_empty() {
+            return Vec::new();
+        }
+        let is_dataset_key = |k: &str| {
+            k.split_once('-').is_some_and(|(prefix, n)| {
+                !prefix.is_empty()
+                    && prefix.chars().all(|c| c.is_ascii_alphabetic())
+                    && !n.is_empty()
+                    && n.chars().all(|c| c.is_ascii_digit())
+            })
+        };
+        if map.iter().all(|(k, v)| v.is_object() && is_dataset_key(k)) {
+            return map
+                .iter()
+                .map(|(k, v)| {
+                    let mut item = v.clone();
+                    if let Some(obj) = item.as_object_mut() {
+                        obj.entry("dataset_id").or_insert_with(|| Value::String(k.clone()));
+                    }
+                    item
+                })
+                .collect();
+        }
+    }
+    vec![json.clone()]
+}
+
+// Следующая страница: ссылка next в теле (если ключ есть, даже null, ей верим),
+// иначе page/pages, иначе offset по total/count
+fn next_page_url(json: &Value, current: &str, fetched: u64) -> Option<String> {
+    for ptr in ["/next", "/links/next", "/_links/next/href", "/pagination/next", "/meta/next"] {
+        if let Some(link) = json.pointer(ptr) {
+            let link = link.as_str().filter(|s| !s.is_empty())?;
+            return reqwest::Url::parse(current).and_then(|u| u.join(link)).ok().map(String::from);
+        }
+    }
+    let int = |ptrs: &[&str]| ptrs.iter().find_map(|p| json.pointer(p).and_then(num)).map(|n| n as u64);
+
+    if let (Some(page), Some(pages)) = (
+        int(&["/page", "/pagination/page", "/meta/page"]),
+        int(&["/pages", "/total_pages", "/pagination/pages", "/meta/total_pages"]),
+    ) {
+        return (page < pages).then(|| with_query_param(current, "page", &(page + 1).to_string()));
+    }
+    let total = int(&["/total", "/count", "/total_count", "/pagination/total", "/meta/total"])?;
+    (fetched < total).then(|| with_query_param(current, "offset", &fetched.to_string()))
+}
+
 fn s_pick(v: &Value, keys: &[&str]) -> Option<String> {
     for k in keys {
         if let Some(x) = v.get(*k) {
@@ -168,6 +1395,132 @@ fn t_pick(v: &Value, keys: &[&str]) -> Option<DateTime<Utc>> {
     None
 }
 
+pub struct JobService {
+    pool: PgPool,
+    jobs: Arc<JobRegistry>,
+    leader: Arc<LeaderElection>,
+}
+
+impl JobService {
+    pub fn new(pool: PgPool, jobs: Arc<JobRegistry>, leader: Arc<LeaderElection>) -> Self {
+        Self { pool, jobs, leader }
+    }
+
+    pub async fn runs(&self, job: Option<&str>, outcome: Option<&str>, limit: i64) -> Result<Vec<SyncRun>, ApiError> {
+        SyncRunRepo::list(&self.pool, job, outcome, limit).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<Value>, ApiError> {
+        let last = SyncRunRepo::latest_per_job(&self.pool, None).await?;
+        let success = SyncRunRepo::latest_per_job(&self.pool, Some("success")).await?;
+        Ok(self
+            .jobs
+            .jobs()
+            .iter()
+            .map(|h| {
+                job_view(
+                    h,
+                    self.leader.is_leader(),
+                    last.iter().find(|r| r.job == h.name()),
+                    success.iter().find(|r| r.job == h.name()),
+                )
+            })
+            .collect())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Value, ApiError> {
+        let handle = self.handle(name)?;
+        let last = SyncRunRepo::list(&self.pool, Some(name), None, 1).await?;
+        let success = SyncRunRepo::list(&self.pool, Some(name), Some("success"), 1).await?;
+        Ok(job_view(&handle, self.leader.is_leader(), last.first(), success.first()))
+    }
+
+    pub async fn trigger(&self, name: &str) -> Result<Value, ApiError> {
+        self.handle(name)?.trigger();
+        self.get(name).await
+    }
+
+    pub async fn set_paused(&self, name: &str, paused: bool) -> Result<Value, ApiError> {
+        let handle = self.handle(name)?;
+        JobSettingsRepo::set_paused(&self.pool, name, paused).await?;
+        handle.set_paused(paused);
+        JobSettingsRepo::notify(&self.pool, name).await?;
+        self.get(name).await
+    }
+
+    pub async fn reschedule(&self, name: &str, patch: JobPatch) -> Result<Value, ApiError> {
+        let handle = self.handle(name)?;
+        let cron = patch.cron.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        if patch.interval_seconds.is_some() && cron.is_some() {
+            return Err(ApiError::Validation("set either interval_seconds or cron, not both".to_string()));
+        }
+        if patch.interval_seconds.is_none() && cron.is_none() && patch.jitter_seconds.is_none() {
+            return Err(ApiError::Validation("nothing to change: interval_seconds, cron or jitter_seconds".to_string()));
+        }
+        if patch.interval_seconds.is_some_and(|s| s < 1) {
+            return Err(ApiError::Validation("interval_seconds must be at least 1".to_string()));
+        }
+        if patch.jitter_seconds.is_some_and(|s| s < 0) {
+            return Err(ApiError::Validation("jitter_seconds must not be negative".to_string()));
+        }
+        if patch.interval_seconds.is_some_and(|s| s > MAX_SCHEDULE_SECS)
+            || patch.jitter_seconds.is_some_and(|s| s > MAX_SCHEDULE_SECS)
+        {
+            return Err(ApiError::Validation(format!(
+                "interval_seconds and jitter_seconds must be at most {}",
+                MAX_SCHEDULE_SECS
+            )));
+        }
+
+        let settings = JobSettings {
+            name: name.to_string(),
+            paused: handle.is_paused(),
+            interval_seconds: patch.interval_seconds,
+            cron: cron.map(str::to_string),
+            jitter_seconds: patch.jitter_seconds,
+            updated_at: Utc::now(),
+        };
+        let schedule = handle
+            .schedule()
+            .with_settings(&settings)
+            .map_err(|e| ApiError::Validation(e.to_string()))?;
+        JobSettingsRepo::set_schedule(&self.pool, name, patch.interval_seconds, cron, patch.jitter_seconds).await?;
+        handle.reschedule(schedule);
+        JobSettingsRepo::notify(&self.pool, name).await?;
+        self.get(name).await
+    }
+
+    fn handle(&self, name: &str) -> Result<Arc<JobHandle>, ApiError> {
+        self.jobs
+            .get(name)
+            .ok_or_else(|| ApiError::NotFound(format!("job {} not found", name)))
+    }
+}
+
+// standby - реплика не ведущая: по расписанию задачу запускает другая,
+// и время следующего запуска здесь неизвестно
+fn job_view(handle: &JobHandle, is_leader: bool, last: Option<&SyncRun>, success: Option<&SyncRun>) -> Value {
+    let state = if handle.is_running() {
+        "running"
+    } else if handle.is_paused() {
+        "paused"
+    } else if !is_leader {
+        "standby"
+    } else {
+        "scheduled"
+    };
+    serde_json::json!({
+        "name": handle.name(),
+        "state": state,
+        "paused": handle.is_paused(),
+        "schedule": handle.schedule().describe(),
+        "timeout_seconds": handle.timeout().as_secs(),
+        "next_run_at": if is_leader { handle.next_run_at() } else { None },
+        "last_run": last,
+        "last_success_at": success.and_then(|r| r.finished_at)
+    })
+}
+
 pub struct SpaceCacheService {
     pool: PgPool,
     client: HttpClient,
@@ -178,34 +1531,29 @@ impl SpaceCacheService {
         Self { pool, client }
     }
 
-    pub async fn fetch_apod(&self) -> Result<(), ApiError> {
+    pub async fn fetch_apod(&self) -> Result<bool, ApiError> {
         let json = self.client.get_apod().await?;
-        CacheRepo::insert(&self.pool, "apod", json).await?;
-        Ok(())
+        CacheRepo::insert(&self.pool, "apod", json).await
     }
 
-    pub async fn fetch_neo(&self) -> Result<(), ApiError> {
+    pub async fn fetch_neo(&self) -> Result<bool, ApiError> {
         let json = self.client.get_neo().await?;
-        CacheRepo::insert(&self.pool, "neo", json).await?;
-        Ok(())
+        CacheRepo::insert(&self.pool, "neo", json).await
     }
 
-    pub async fn fetch_donki_flr(&self) -> Result<(), ApiError> {
+    pub async fn fetch_donki_flr(&self) -> Result<bool, ApiError> {
         let json = self.client.get_donki_flr().await?;
-        CacheRepo::insert(&self.pool, "flr", json).await?;
-        Ok(())
+        CacheRepo::insert(&self.pool, "flr", json).await
     }
 
-    pub async fn fetch_donki_cme(&self) -> Result<(), ApiError> {
+    pub async fn fetch_donki_cme(&self) -> Result<bool, ApiError> {
         let json = self.client.get_donki_cme().await?;
-        CacheRepo::insert(&self.pool, "cme", json).await?;
-        Ok(())
+        CacheRepo::insert(&self.pool, "cme", json).await
     }
 
-    pub async fn fetch_spacex(&self) -> Result<(), ApiError> {
+    pub async fn fetch_spacex(&self) -> Result<bool, ApiError> {
         let json = self.client.get_spacex().await?;
-        CacheRepo::insert(&self.pool, "spacex", json).await?;
-        Ok(())
+        CacheRepo::insert(&self.pool, "spacex", json).await
     }
 
     pub async fn get_latest(&self, source: &str) -> Result<Option<crate::domain::SpaceCache>, ApiError> {
@@ -233,8 +1581,8 @@ impl SpaceCacheService {
             .map(|c| serde_json::json!({"at": c.fetched_at, "payload": c.payload}))
             .unwrap_or(serde_json::json!({}));
 
-        let iss_last = IssRepo::get_last(&self.pool).await?
-            .map(|l| serde_json::json!({"at": l.fetched_at, "payload": l.payload}))
+        let iss_last = IssRepo::get_last(&self.pool, ISS_NORAD_ID).await?
+            .map(|l| serde_json::json!({"at": l.fetched_at, "payload": l.payload, "position": l.position}))
             .unwrap_or(serde_json::json!({}));
 
         let osdr_count = OsdrRepo::count(&self.pool).await?;
@@ -251,3 +1599,130 @@ impl SpaceCacheService {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn diff(old: Value, new: Value) -> Vec<Value> {
+        let mut ops = Vec::new();
+        json_diff(&old, &new, "", &mut ops);
+        ops
+    }
+
+    #[test]
+    fn diff_object_keys() {
+        assert!(diff(json!({"a": 1}), json!({"a": 1})).is_empty());
+        assert_eq!(
+            diff(json!({"a": 1, "b": 2}), json!({"a": 3, "c": 4})),
+            vec![
+                json!({"op": "replace", "path": "/a", "old": 1, "value": 3}),
+                json!({"op": "remove", "path": "/b", "old": 2}),
+                json!({"op": "add", "path": "/c", "value": 4}),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_nested_objects() {
+        assert_eq!(
+            diff(json!({"meta": {"title": "x", "tags": {"a": true}}}), json!({"meta": {"title": "y", "tags": {}}})),
+            vec![
+                json!({"op": "remove", "path": "/meta/tags/a", "old": true}),
+                json!({"op": "replace", "path": "/meta/title", "old": "x", "value": "y"}),
+            ]
+        );
+        // смена типа - целиком replace
+        assert_eq!(
+            diff(json!({"a": {"b": 1}}), json!({"a": [1]})),
+            vec![json!({"op": "replace", "path": "/a", "old": {"b": 1}, "value": [1]})]
+        );
+    }
+
+    #[test]
+    fn diff_arrays() {
+        assert_eq!(
+            diff(json!([1, 2]), json!([1, 5, 6, 7])),
+            vec![
+                json!({"op": "replace", "path": "/1", "old": 2, "value": 5}),
+                json!({"op": "add", "path": "/2", "value": 6}),
+                json!({"op": "add", "path": "/3", "value": 7}),
+            ]
+        );
+        // удаление с конца
+        assert_eq!(
+            diff(json!({"xs": [1, 2, 3]}), json!({"xs": [1]})),
+            vec![
+                json!({"op": "remove", "path": "/xs/2", "old": 3}),
+                json!({"op": "remove", "path": "/xs/1", "old": 2}),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_escapes_pointer_keys() {
+        assert_eq!(
+            diff(json!({"a/b": 1, "m~n": 1}), json!({"a/b": 2, "m~n": 2, "~/": 0})),
+            vec![
+                json!({"op": "replace", "path": "/a~1b", "old": 1, "value": 2}),
+                json!({"op": "replace", "path": "/m~0n", "old": 1, "value": 2}),
+                json!({"op": "add", "path": "/~0~1", "value": 0}),
+            ]
+        );
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let key = "2025-01-02T03:04:05+00:00";
+        for (sort, key) in [(OsdrSort::UpdatedAt, key), (OsdrSort::InsertedAt, key), (OsdrSort::Title, "a/b & \"c\" ∑")] {
+            for descending in [false, true] {
+                let cursor = encode_cursor(sort, descending, key, 42);
+                assert!(cursor.bytes().all(|b| b.is_ascii_hexdigit()));
+                let (k, id) = decode_cursor(&cursor, sort, descending).unwrap();
+                assert_eq!((k.as_str(), id), (key, 42));
+            }
+        }
+        let cursor = encode_cursor(OsdrSort::UpdatedAt, true, "-infinity", 7);
+        assert_eq!(decode_cursor(&cursor, OsdrSort::UpdatedAt, true).unwrap(), ("-infinity".to_string(), 7));
+    }
+
+    #[test]
+    fn cursor_rejected_for_other_sort_or_order() {
+        let cursor = encode_cursor(OsdrSort::UpdatedAt, true, "2025-01-02T03:04:05+00:00", 42);
+        for (sort, descending) in [(OsdrSort::UpdatedAt, false), (OsdrSort::InsertedAt, true), (OsdrSort::Title, true)] {
+            match decode_cursor(&cursor, sort, descending) {
+                Err(ApiError::Validation(msg)) => assert!(msg.starts_with("cursor was issued for sort=updated_at order=desc"), "{msg}"),
+                other => panic!("expected validation error, got {other:?}"),
+            }
+        }
+        // битый hex и ключ не того типа
+        assert!(matches!(decode_cursor("zz", OsdrSort::Title, false), Err(ApiError::Validation(_))));
+        let cursor = encode_cursor(OsdrSort::InsertedAt, false, "yesterday", 1);
+        assert!(matches!(decode_cursor(&cursor, OsdrSort::InsertedAt, false), Err(ApiError::Validation(_))));
+    }
+
+    #[test]
+    fn track_split_at_antimeridian_both_ways() {
+        let t = |m: i64| DateTime::<Utc>::from_timestamp(m * 60, 0).unwrap();
+        // (время, lat, lon): на восток через +180, затем обратно на запад
+        let points = [(t(0), 0.0, 170.0), (t(1), 10.0, -170.0), (t(2), 20.0, -170.0), (t(3), 30.0, 170.0)];
+        let segments = split_antimeridian(&points);
+        let parts: Vec<_> = segments.iter().map(|s| (s.from, s.to, s.coordinates.clone())).collect();
+        assert_eq!(
+            parts,
+            vec![
+                (t(0), t(0), vec![[170.0, 0.0], [180.0, 5.0]]),
+                (t(1), t(2), vec![[-180.0, 5.0], [-170.0, 10.0], [-170.0, 20.0], [-180.0, 25.0]]),
+                (t(3), t(3), vec![[180.0, 25.0], [170.0, 30.0]]),
+            ]
+        );
+
+        // без перехода - один отрезок как есть
+        let points = [(t(0), 0.0, -10.0), (t(1), 5.0, 10.0)];
+        let segments = split_antimeridian(&points);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].coordinates, vec![[-10.0, 0.0], [10.0, 5.0]]);
+        assert!(split_antimeridian(&[]).is_empty());
+    }
+}